@@ -13,6 +13,8 @@ const DEFAULT_PROGRAMS = {
   systemProgram: web3.SystemProgram.programId,
   associatedTokenProgram: utils.token.ASSOCIATED_PROGRAM_ID,
   tokenProgram: TOKEN_PROGRAM_ID,
+  baseTokenProgram: TOKEN_PROGRAM_ID,
+  poolTokenProgram: TOKEN_PROGRAM_ID,
 };
 
 class luciferProgram {
@@ -99,7 +101,15 @@ class luciferProgram {
     };
   };
 
-  initializePool = async (mint: Address, baseMint: Address, fee: BN, amount: BN, stableAmount: BN, baseAmount: BN) => {
+  initializePool = async (
+    mint: Address,
+    baseMint: Address,
+    fee: BN,
+    amount: BN,
+    stableAmount: BN,
+    baseAmount: BN,
+    stableDecimals: number,
+  ) => {
     const pool = web3.Keypair.generate();
     const PDAs = await this.getPoolPDAs(pool.publicKey, mint, baseMint);
     const wallet = this._provider.wallet;
@@ -113,7 +123,7 @@ class luciferProgram {
 
     console.log("PDAs", PDAs.lptMint.toBase58());
     const instruction = await this.program.methods
-      .initializePool(fee, amount, stableAmount, baseAmount)
+      .initializePool(fee, amount, stableAmount, baseAmount, stableDecimals)
       .accounts({
         authority: wallet.publicKey,
         ...PDAs,
@@ -126,70 +136,86 @@ class luciferProgram {
     return { txId, address: pool.publicKey };
   };
 
-  mintStable = async (pool: Address, amount: BN) => {
+  mintStable = async (pool: Address, amount: BN, native = false) => {
     const { mint, baseMint } = await this.program.account.pool.fetch(pool);
     const PDAs = await this.getPoolPDAs(pool, mint, baseMint);
     const wallet = this._provider.wallet;
     const tokenAccounts = await this.getTokenAccounts(wallet.publicKey, pool, mint, baseMint);
+    // Fresh keypair for the temporary wrapped SOL account, it only signs with native
+    const wsolAccount = web3.Keypair.generate();
     const txId = await this.program.methods
-      .mintStable(amount)
+      .mintStable(amount, native)
       .accounts({
         authority: wallet.publicKey,
         ...PDAs,
         ...tokenAccounts,
+        wsolAccount: wsolAccount.publicKey,
         ...DEFAULT_PROGRAMS,
       })
+      .signers(native ? [wsolAccount] : [])
       .rpc();
     return { txId };
   };
 
-  burnStable = async (pool: Address, amount: BN) => {
+  burnStable = async (pool: Address, amount: BN, native = false) => {
     const { mint, baseMint } = await this.program.account.pool.fetch(pool);
     const PDAs = await this.getPoolPDAs(pool, mint, baseMint);
     const wallet = this._provider.wallet;
     const tokenAccounts = await this.getTokenAccounts(wallet.publicKey, pool, mint, baseMint);
+    // Fresh keypair for the temporary wrapped SOL account, it only signs with native
+    const wsolAccount = web3.Keypair.generate();
     const txId = await this.program.methods
-      .burnStable(amount)
+      .burnStable(amount, native)
       .accounts({
         authority: wallet.publicKey,
         ...PDAs,
         ...tokenAccounts,
+        wsolAccount: wsolAccount.publicKey,
         ...DEFAULT_PROGRAMS,
       })
+      .signers(native ? [wsolAccount] : [])
       .rpc();
     return { txId };
   };
 
-  addLiquidity = async (pool: Address, amount: BN, stableAmount: BN, baseAmount: BN) => {
+  addLiquidity = async (pool: Address, amount: BN, stableAmount: BN, baseAmount: BN, native = false) => {
     const { mint, baseMint } = await this.program.account.pool.fetch(pool);
     const PDAs = await this.getPoolPDAs(pool, mint, baseMint);
     const wallet = this._provider.wallet;
     const tokenAccounts = await this.getTokenAccounts(wallet.publicKey, pool, mint, baseMint);
+    // Fresh keypair for the temporary wrapped SOL account, it only signs with native
+    const wsolAccount = web3.Keypair.generate();
     const txId = await this.program.methods
-      .addLiquidity(amount, stableAmount, baseAmount)
+      .addLiquidity(amount, stableAmount, baseAmount, native)
       .accounts({
         authority: wallet.publicKey,
         ...PDAs,
         ...tokenAccounts,
+        wsolAccount: wsolAccount.publicKey,
         ...DEFAULT_PROGRAMS,
       })
+      .signers(native ? [wsolAccount] : [])
       .rpc();
     return { txId };
   };
 
-  removeLiquidity = async (pool: Address, lpt_amount: BN) => {
+  removeLiquidity = async (pool: Address, lpt_amount: BN, native = false) => {
     const { mint, baseMint } = await this.program.account.pool.fetch(pool);
     const PDAs = await this.getPoolPDAs(pool, mint, baseMint);
     const wallet = this._provider.wallet;
     const tokenAccounts = await this.getTokenAccounts(wallet.publicKey, pool, mint, baseMint);
+    // Fresh keypair for the temporary wrapped SOL account, it only signs with native
+    const wsolAccount = web3.Keypair.generate();
     const txId = await this.program.methods
-      .removeLiquidity(lpt_amount)
+      .removeLiquidity(lpt_amount, native)
       .accounts({
         authority: wallet.publicKey,
         ...PDAs,
         ...tokenAccounts,
+        wsolAccount: wsolAccount.publicKey,
         ...DEFAULT_PROGRAMS,
       })
+      .signers(native ? [wsolAccount] : [])
       .rpc();
     return { txId };
   };
@@ -228,36 +254,44 @@ class luciferProgram {
     return { txId };
   };
 
-  buy = async (pool: Address, stable_amount: BN, base_amount: BN) => {
+  buy = async (pool: Address, stable_amount: BN, base_amount: BN, native = false) => {
     const { mint, baseMint } = await this.program.account.pool.fetch(pool);
     const PDAs = await this.getPoolPDAs(pool, mint, baseMint);
     const wallet = this._provider.wallet;
     const tokenAccounts = await this.getTokenAccounts(wallet.publicKey, pool, mint, baseMint);
+    // Fresh keypair for the temporary wrapped SOL account, it only signs with native
+    const wsolAccount = web3.Keypair.generate();
     const txId = await this.program.methods
-      .buy(stable_amount, base_amount)
+      .buy(stable_amount, base_amount, native)
       .accounts({
         authority: wallet.publicKey,
         ...PDAs,
         ...tokenAccounts,
+        wsolAccount: wsolAccount.publicKey,
         ...DEFAULT_PROGRAMS,
       })
+      .signers(native ? [wsolAccount] : [])
       .rpc();
     return { txId };
   };
 
-  sell = async (pool: Address, amount: BN) => {
+  sell = async (pool: Address, amount: BN, native = false) => {
     const { mint, baseMint } = await this.program.account.pool.fetch(pool);
     const PDAs = await this.getPoolPDAs(pool, mint, baseMint);
     const wallet = this._provider.wallet;
     const tokenAccounts = await this.getTokenAccounts(wallet.publicKey, pool, mint, baseMint);
+    // Fresh keypair for the temporary wrapped SOL account, it only signs with native
+    const wsolAccount = web3.Keypair.generate();
     const txId = await this.program.methods
-      .sell(amount)
+      .sell(amount, native)
       .accounts({
         authority: wallet.publicKey,
         ...PDAs,
         ...tokenAccounts,
+        wsolAccount: wsolAccount.publicKey,
         ...DEFAULT_PROGRAMS,
       })
+      .signers(native ? [wsolAccount] : [])
       .rpc();
     return { txId };
   };
@@ -10,4 +10,92 @@ pub enum ErrorCode {
     Slippage,
     #[msg("Invalid Amount")]
     InvalidAmount,
+    #[msg("Math operation overflow")]
+    MathOverflow,
+    #[msg("Cannot get current time")]
+    InvalidCurrentTime,
+    #[msg("Invalid time range")]
+    InvalidTimeRange,
+    #[msg("Farm reward is not enough for the emission schedule")]
+    InsufficientReward,
+    #[msg("Invalid mint")]
+    InvalidMint,
+    #[msg("Invalid fee")]
+    InvalidFee,
+    #[msg("A flash loan is already in progress")]
+    FlashLoanInProgress,
+    #[msg("No flash loan to repay")]
+    NoFlashLoan,
+    #[msg("Missing flash loan repay instruction")]
+    MissingFlashRepay,
+    #[msg("Invalid amplification coefficient")]
+    InvalidAmp,
+    #[msg("Missing oracle account")]
+    MissingOracle,
+    #[msg("Invalid oracle account")]
+    InvalidOracle,
+    #[msg("Stale oracle price")]
+    StaleOracle,
+    #[msg("Pool price is too far from the oracle price")]
+    OracleBandExceeded,
+    #[msg("Price impact is too high")]
+    PriceImpactExceeded,
+    #[msg("Trading is halted")]
+    TradingHalted,
+    #[msg("Limit price not reached")]
+    LimitPriceNotReached,
+    #[msg("Order expired")]
+    OrderExpired,
+    #[msg("Order not expired yet")]
+    OrderNotExpired,
+    #[msg("DCA order is not due yet")]
+    DcaNotDue,
+    #[msg("LPT is still locked")]
+    StillLocked,
+    #[msg("Referral doesn't belong to the pool")]
+    InvalidReferral,
+    #[msg("Too many fee tiers")]
+    TooManyFeeTiers,
+    #[msg("Invalid fee discount proof")]
+    InvalidFeeProof,
+    #[msg("The pool is withdraw-only")]
+    PoolNotActive,
+    #[msg("The pool must be withdraw-only")]
+    PoolNotWithdrawOnly,
+    #[msg("The pool still has liquidity providers or debts")]
+    PoolNotEmpty,
+    #[msg("A treasury holds less than its ledger")]
+    LedgerMismatch,
+    #[msg("Stable supply isn't backed by base reserves and debts")]
+    UnbackedStable,
+    #[msg("The pool invariant decreased")]
+    InvariantDecreased,
+    #[msg("Missing PDA bump")]
+    MissingBump,
+    #[msg("Insufficient liquidity in the pool")]
+    InsufficientLiquidity,
+    #[msg("Initial liquidity is below the minimum")]
+    InsufficientInitialLiquidity,
+    #[msg("Stable decimals are too far from the base decimals")]
+    InvalidDecimals,
+    #[msg("The pool debt ceiling is exceeded")]
+    DebtCeilingExceeded,
+    #[msg("Invalid collateral ratio")]
+    InvalidCollateralRatio,
+    #[msg("The vault would be undercollateralized")]
+    Undercollateralized,
+    #[msg("The vault is above the liquidation ratio")]
+    VaultHealthy,
+    #[msg("A wallet can't refer its own trades")]
+    SelfReferral,
+    #[msg("The farm still has stakes")]
+    FarmNotEmpty,
+    #[msg("The vault still has debt or collateral")]
+    VaultNotEmpty,
+    #[msg("An oracle-priced base can't back the protocol stable")]
+    OraclePricedBase,
+    #[msg("The pool must be migrated first")]
+    PoolNotMigrated,
+    #[msg("The repayment didn't cover the amount owed")]
+    InsufficientRepayment,
 }
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+// Treasury amounts minus ledger amounts, positive for a surplus
+#[event]
+pub struct SyncEvent {
+    pub pool: Pubkey,
+    pub discrepancy: i64,
+    pub stable_discrepancy: i64,
+    pub base_discrepancy: i64,
+}
+
+#[event]
+pub struct SkimEvent {
+    pub pool: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub stable_amount: u64,
+    pub base_amount: u64,
+}
+
+// Ratios are in PRECISION units
+#[event]
+pub struct BaseLedgerEvent {
+    pub pool: Pubkey,
+    pub base_treasury_amount: u64,
+    pub stable_supply: u64,
+    pub backing: u64,
+    pub backing_ratio: u64,
+    pub lending_reserve: u64,
+    pub borrowed: u64,
+    pub legacy_borrowed: u64,
+    pub lending_utilization: u64,
+}
+
+// Borsh layout of the view_solvency return data. Collateral is the base treasury less
+// the lending reserve plus the legacy loans, collateral_ratio is in PRECISION units over
+// the stable not minted by CDP vaults
+#[event]
+pub struct SolvencyEvent {
+    pub pool: Pubkey,
+    pub timestamp: i64,
+    pub stable_supply: u64,
+    pub base_treasury_amount: u64,
+    pub lending_reserve: u64,
+    pub borrowed: u64,
+    pub legacy_borrowed: u64,
+    pub vault_debt: u64,
+    pub collateral: u64,
+    pub collateral_ratio: u64,
+}
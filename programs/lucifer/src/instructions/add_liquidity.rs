@@ -1,106 +1,138 @@
+use crate::errors::ErrorCode;
 use crate::instructions::*;
-use crate::oracle::*;
 use crate::schema::*;
+use crate::utils::*;
 
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 
 #[derive(Accounts)]
 pub struct AddLiquidity<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
   // Pool's info
-  #[account(mut, has_one=mint)]
+  #[account(
+    mut,
+    has_one = mint,
+    has_one = base_mint,
+    has_one = stable_mint,
+    has_one = lpt_mint
+  )]
   pub pool: Account<'info, Pool>,
   #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
   /// CHECK: Just a pure account
   pub treasurer: AccountInfo<'info>,
   // Pool's Mints
-  pub mint: Box<Account<'info, token::Mint>>,
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
   #[account(
     mut,
     seeds = [b"stable_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub stable_mint: Account<'info, token::Mint>,
-  pub base_mint: Box<Account<'info, token::Mint>>,
+  pub stable_mint: InterfaceAccount<'info, token_interface::Mint>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
   #[account(
     mut,
     seeds = [b"lpt_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub lpt_mint: Account<'info, token::Mint>,
+  pub lpt_mint: InterfaceAccount<'info, token_interface::Mint>,
   // Pool's token account
   #[account(
     mut,
     associated_token::mint = mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
   )]
-  pub treasury: Box<Account<'info, token::TokenAccount>>,
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = stable_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
   )]
-  pub base_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = lpt_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Wallet's Token Accounts
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
   )]
-  pub token_account: Box<Account<'info, token::TokenAccount>>,
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = stable_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
   )]
-  pub base_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = lpt_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Temporary wrapped SOL account, a fresh keypair that only signs with native
+  #[account(mut)]
+  /// CHECK: Created and closed within the instruction when native
+  pub wsol_account: AccountInfo<'info>,
 
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn exec(
-  ctx: Context<AddLiquidity>,
+pub fn exec<'info>(
+  ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>,
   amount: u64,
   stable_amount: u64,
   base_amount: u64,
+  native: bool,
 ) -> Result<()> {
+  let surpluses = calc_surpluses(
+    &ctx.accounts.pool,
+    &ctx.accounts.treasury,
+    &ctx.accounts.stable_treasury,
+    &ctx.accounts.base_treasury,
+  )?;
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  check_active(&ctx.accounts.pool)?;
   let mut total_stable_amount = stable_amount;
 
   // Call inner instructions Mint Stable
   if base_amount > 0 {
+    let base_balance = ctx.accounts.pool.base_balance;
     let accounts = &mut MintStable {
       authority: ctx.accounts.authority.clone(),
       pool: ctx.accounts.pool.clone(),
@@ -111,76 +143,159 @@ pub fn exec(
       stable_treasury: ctx.accounts.stable_treasury.clone(),
       base_token_account: ctx.accounts.base_token_account.clone(),
       stable_token_account: ctx.accounts.stable_token_account.clone(),
+      wsol_account: ctx.accounts.wsol_account.clone(),
       system_program: ctx.accounts.system_program.clone(),
-      token_program: ctx.accounts.token_program.clone(),
+      base_token_program: ctx.accounts.base_token_program.clone(),
+      pool_token_program: ctx.accounts.pool_token_program.clone(),
       associated_token_program: ctx.accounts.associated_token_program.clone(),
       rent: ctx.accounts.rent.clone(),
     };
-    let mint_to_context = Context::new(&ctx.program_id, accounts, &[], ctx.bumps.clone());
-    mint_stable::exec(mint_to_context, base_amount).unwrap();
+    let bumps = MintStableBumps {
+      treasurer: ctx.bumps.treasurer,
+      stable_mint: ctx.bumps.stable_mint,
+    };
+    let mint_to_context = Context::new(&ctx.program_id, accounts, &[], bumps);
+    mint_stable::exec(mint_to_context, base_amount, native)?;
+    // The nested handler wrote its own copy of the pool, persist it before reloading
+    accounts.pool.exit(ctx.program_id)?;
     ctx.accounts.pool.reload()?;
-    total_stable_amount += base_amount;
+    // Stable minted for the base that reached the treasury
+    let base_received = ctx
+      .accounts
+      .pool
+      .base_balance
+      .checked_sub(base_balance)
+      .ok_or(ErrorCode::MathOverflow)?;
+    let stable_amount = ctx
+      .accounts
+      .pool
+      .calc_stable_amount(base_received)
+      .ok_or(ErrorCode::MathOverflow)?;
+    total_stable_amount = total_stable_amount
+      .checked_add(stable_amount)
+      .ok_or(ErrorCode::MathOverflow)?;
   }
 
-  let pool = &mut ctx.accounts.pool;
-  // Transfer Mint
+  // Transfer Mint, straight from lamports with native
+  let mut amount = amount;
   if amount > 0 {
-    token::transfer(
-      CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
-          from: ctx.accounts.token_account.to_account_info(),
-          to: ctx.accounts.treasury.to_account_info(),
-          authority: ctx.accounts.authority.to_account_info(),
-        },
-      ),
+    let wrapped = native && ctx.accounts.token_account.is_native();
+    let source = if wrapped {
+      wrap_sol(
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+        &ctx.accounts.rent,
+        &ctx.accounts.authority,
+        &ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.wsol_account,
+        amount,
+      )?;
+      ctx.accounts.wsol_account.to_account_info()
+    } else {
+      ctx.accounts.token_account.to_account_info()
+    };
+    amount = transfer_in(
+      &ctx.accounts.token_program,
+      source,
+      &mut ctx.accounts.treasury,
+      &ctx.accounts.mint,
+      ctx.accounts.authority.to_account_info(),
+      &[],
       amount,
     )?;
+    if wrapped {
+      unwrap_sol(
+        &ctx.accounts.token_program,
+        &ctx.accounts.authority,
+        &ctx.accounts.wsol_account,
+      )?;
+    }
   }
   // Transfer Stable Mint
   if total_stable_amount > 0 {
-    token::transfer(
-      CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
-          from: ctx.accounts.stable_token_account.to_account_info(),
-          to: ctx.accounts.stable_treasury.to_account_info(),
-          authority: ctx.accounts.authority.to_account_info(),
-        },
-      ),
+    total_stable_amount = transfer_in(
+      &ctx.accounts.pool_token_program,
+      ctx.accounts.stable_token_account.to_account_info(),
+      &mut ctx.accounts.stable_treasury,
+      &ctx.accounts.stable_mint,
+      ctx.accounts.authority.to_account_info(),
+      &[],
       total_stable_amount,
     )?;
   }
+  let pool = &mut ctx.accounts.pool;
   // Mint to LPT
   let seeds: &[&[&[u8]]] = &[&[
     "treasurer".as_ref(),
     &pool.key().to_bytes(),
-    &[*ctx.bumps.get("treasurer").unwrap()],
+    &[ctx.bumps.treasurer],
   ]];
 
   let amounts = vec![amount, total_stable_amount];
   let reserves = vec![pool.balance, pool.stable_balance];
   let supply = ctx.accounts.lpt_mint.supply;
-  let lpt_amount = calc_lpt_receive_add_full_side(supply, &amounts, &reserves, pool.fee).unwrap();
-  let lpt_amount_ignore_fee =
-    calc_lpt_receive_add_full_side(supply, &amounts, &reserves, 0).unwrap();
+  let lpt_amount = pool
+    .calc_lpt_receive_add(supply, &amounts, &reserves, pool.fee, current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let lpt_amount_ignore_fee = pool
+    .calc_lpt_receive_add(supply, &amounts, &reserves, 0, current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
 
   let mint_to_lpt = CpiContext::new_with_signer(
-    ctx.accounts.token_program.to_account_info(),
-    token::MintTo {
+    ctx.accounts.pool_token_program.to_account_info(),
+    token_interface::MintTo {
       to: ctx.accounts.lpt_token_account.to_account_info(),
       mint: ctx.accounts.lpt_mint.to_account_info(),
       authority: ctx.accounts.treasurer.to_account_info(),
     },
     seeds,
   );
-  token::mint_to(mint_to_lpt, lpt_amount)?;
+  token_interface::mint_to(mint_to_lpt, lpt_amount)?;
   msg!("Add liquidity mint To {}", lpt_amount);
   // Update pool info
-  pool.lpt_supply += lpt_amount;
-  pool.balance += amount;
-  pool.stable_balance += total_stable_amount;
-  pool.total_lpt_fee += lpt_amount_ignore_fee.checked_sub(lpt_amount).unwrap();
+  pool.lpt_supply = pool
+    .lpt_supply
+    .checked_add(lpt_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.balance = pool
+    .balance
+    .checked_add(amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.stable_balance = pool
+    .stable_balance
+    .checked_add(total_stable_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let lpt_fee = lpt_amount_ignore_fee
+    .checked_sub(lpt_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.total_lpt_fee = pool
+    .total_lpt_fee
+    .checked_add(lpt_fee)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Referrer's share of the fee, minted in LPT on claim
+  if let Some(mut referral) = find_referral(
+    ctx.program_id,
+    &pool.key(),
+    &ctx.accounts.authority.key(),
+    ctx.remaining_accounts,
+  )? {
+    let referral_fee = pool
+      .calc_referral_fee(lpt_fee)
+      .ok_or(ErrorCode::MathOverflow)?;
+    referral
+      .add_liquidity(referral_fee, lpt_amount)
+      .ok_or(ErrorCode::MathOverflow)?;
+    referral.exit(ctx.program_id)?;
+  }
+
+  check_invariants(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.treasury,
+    &mut ctx.accounts.stable_treasury,
+    &mut ctx.accounts.base_treasury,
+    &mut ctx.accounts.stable_mint,
+    surpluses,
+  )?;
 
   Ok(())
 }
@@ -0,0 +1,114 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct CloseFarm<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Farm's info
+  #[account(
+    mut,
+    close = authority,
+    has_one = authority,
+    has_one = pool,
+    has_one = reward_mint,
+    has_one = vault,
+    has_one = reward_vault
+  )]
+  pub farm: Account<'info, Farm>,
+  pub reward_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Farm's token accounts
+  #[account(mut)]
+  pub vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(mut)]
+  pub reward_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = reward_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = reward_token_program
+  )]
+  pub reward_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub reward_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+// Return the unspent reward of an ended farm once every stake has left
+pub fn exec(ctx: Context<CloseFarm>) -> Result<()> {
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let farm = &ctx.accounts.farm;
+  if !(farm.end_time < current_time) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  if farm.total_staked > 0 || ctx.accounts.vault.amount > 0 {
+    return err!(ErrorCode::FarmNotEmpty);
+  }
+  // Pool signature
+  let pool = &mut ctx.accounts.pool;
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  let reward_amount = ctx.accounts.reward_vault.amount;
+  if reward_amount > 0 {
+    token_interface::transfer_checked(
+      CpiContext::new_with_signer(
+        ctx.accounts.reward_token_program.to_account_info(),
+        token_interface::TransferChecked {
+          from: ctx.accounts.reward_vault.to_account_info(),
+          mint: ctx.accounts.reward_mint.to_account_info(),
+          to: ctx.accounts.reward_token_account.to_account_info(),
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      reward_amount,
+      ctx.accounts.reward_mint.decimals,
+    )?;
+  }
+  msg!("Close farm {}", reward_amount);
+  for (account, reward_token_program) in [
+    (
+      ctx.accounts.vault.to_account_info(),
+      ctx.accounts.pool_token_program.to_account_info(),
+    ),
+    (
+      ctx.accounts.reward_vault.to_account_info(),
+      ctx.accounts.reward_token_program.to_account_info(),
+    ),
+  ] {
+    token_interface::close_account(CpiContext::new_with_signer(
+      reward_token_program,
+      token_interface::CloseAccount {
+        account,
+        destination: ctx.accounts.authority.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ))?;
+  }
+  pool.open_farms = pool
+    .open_farms
+    .checked_sub(1)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(())
+}
@@ -0,0 +1,218 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(
+    mut,
+    close = authority,
+    has_one = authority,
+    has_one = mint,
+    has_one = base_mint,
+    has_one = stable_mint,
+    has_one = lpt_mint
+  )]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mut)]
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mut)]
+  pub lpt_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
+  )]
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = stable_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = base_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = lpt_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
+  )]
+  pub lpt_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = base_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Cert of the initial liquidity
+  #[account(
+    mut,
+    close = authority,
+    has_one = authority,
+    has_one = pool,
+    seeds = [&lpt_mint.key().to_bytes(), &authority.key().to_bytes()], bump
+  )]
+  pub cert: Account<'info, Cert>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<ClosePool>) -> Result<()> {
+  let pool = &ctx.accounts.pool;
+  if pool.status != PoolStatus::WithdrawOnly {
+    return err!(ErrorCode::PoolNotWithdrawOnly);
+  }
+  // Only the locked minimum liquidity held by the treasurer may be left, with no
+  // open debts, no stable circulating outside the pool and no account still
+  // holding funds against it
+  if pool.borrowed > 0
+    || pool.protocol_backing > 0
+    || pool.vault_debt > 0
+    || pool.flash_loan_amount > 0
+    || pool.lending_reserve > 0
+    || pool.referral_reserve > 0
+    || pool.referral_stable_reserve > 0
+    || pool.open_orders > 0
+    || pool.open_farms > 0
+    || pool.open_locks > 0
+    || pool.open_vaults > 0
+    || pool.open_fee_stakes > 0
+    || ctx.accounts.lpt_mint.supply != ctx.accounts.lpt_treasury.amount
+    || ctx.accounts.stable_mint.supply != ctx.accounts.stable_treasury.amount
+  {
+    return err!(ErrorCode::PoolNotEmpty);
+  }
+  // Pool signature
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  // Burn the remaining lpt and stable
+  token_interface::burn(
+    CpiContext::new_with_signer(
+      ctx.accounts.pool_token_program.to_account_info(),
+      token_interface::Burn {
+        from: ctx.accounts.lpt_treasury.to_account_info(),
+        mint: ctx.accounts.lpt_mint.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    ctx.accounts.lpt_treasury.amount,
+  )?;
+  token_interface::burn(
+    CpiContext::new_with_signer(
+      ctx.accounts.pool_token_program.to_account_info(),
+      token_interface::Burn {
+        from: ctx.accounts.stable_treasury.to_account_info(),
+        mint: ctx.accounts.stable_mint.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    ctx.accounts.stable_treasury.amount,
+  )?;
+  // Return the locked minimum liquidity and leftovers to the pool authority
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.treasury.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.token_account.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    ctx.accounts.treasury.amount,
+    ctx.accounts.mint.decimals,
+  )?;
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.base_token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.base_treasury.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
+        to: ctx.accounts.base_token_account.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    ctx.accounts.base_treasury.amount,
+    ctx.accounts.base_mint.decimals,
+  )?;
+  msg!(
+    "Close pool {} {}",
+    ctx.accounts.treasury.amount,
+    ctx.accounts.base_treasury.amount
+  );
+  // Close the treasuries, the mints can't be closed by the token program
+  for (account, token_program) in [
+    (
+      ctx.accounts.treasury.to_account_info(),
+      ctx.accounts.token_program.to_account_info(),
+    ),
+    (
+      ctx.accounts.stable_treasury.to_account_info(),
+      ctx.accounts.pool_token_program.to_account_info(),
+    ),
+    (
+      ctx.accounts.base_treasury.to_account_info(),
+      ctx.accounts.base_token_program.to_account_info(),
+    ),
+    (
+      ctx.accounts.lpt_treasury.to_account_info(),
+      ctx.accounts.pool_token_program.to_account_info(),
+    ),
+  ] {
+    token_interface::close_account(CpiContext::new_with_signer(
+      token_program,
+      token_interface::CloseAccount {
+        account,
+        destination: ctx.accounts.authority.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ))?;
+  }
+  Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct FundFarm<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Farm's info
+  #[account(mut, has_one = authority, has_one = reward_mint, has_one = reward_vault)]
+  pub farm: Account<'info, Farm>,
+  pub reward_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Farm's token accounts
+  #[account(mut)]
+  pub reward_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    mut,
+    associated_token::mint = reward_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = reward_token_program
+  )]
+  pub reward_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub reward_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<FundFarm>, reward_amount: u64, end_time: i64) -> Result<()> {
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let farm = &mut ctx.accounts.farm;
+  // An ended farm cannot be resumed, it would pay rewards for the idle period
+  if !(end_time >= farm.end_time && farm.end_time > current_time) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  // Transfer Reward, only what the vault received is paid out
+  let reward_amount = if reward_amount > 0 {
+    transfer_in(
+      &ctx.accounts.reward_token_program,
+      ctx.accounts.reward_token_account.to_account_info(),
+      &mut ctx.accounts.reward_vault,
+      &ctx.accounts.reward_mint,
+      ctx.accounts.authority.to_account_info(),
+      &[],
+      reward_amount,
+    )?
+  } else {
+    0
+  };
+  farm.update(current_time).ok_or(ErrorCode::MathOverflow)?;
+  farm.total_reward = farm
+    .total_reward
+    .checked_add(reward_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  farm.end_time = end_time;
+  let total_emission = farm
+    .calc_total_emission(end_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if total_emission > farm.total_reward {
+    return err!(ErrorCode::InsufficientReward);
+  }
+  Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct FundLendingReserve<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut, has_one = authority, has_one = base_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = base_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    mut,
+    associated_token::mint = base_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<FundLendingReserve>, amount: u64) -> Result<()> {
+  let base_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.base_treasury)?;
+  if !(amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  let amount = transfer_in(
+    &ctx.accounts.base_token_program,
+    ctx.accounts.base_token_account.to_account_info(),
+    &mut ctx.accounts.base_treasury,
+    &ctx.accounts.base_mint,
+    ctx.accounts.authority.to_account_info(),
+    &[],
+    amount,
+  )?;
+  let pool = &mut ctx.accounts.pool;
+  pool.lending_reserve = pool
+    .lending_reserve
+    .checked_add(amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.base_treasury,
+    base_surplus,
+  )?;
+  Ok(())
+}
@@ -0,0 +1,115 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct InitializeFarm<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut, has_one = authority, has_one = lpt_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  pub lpt_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Farm's info
+  #[account(init, payer = authority, space = Farm::LEN)]
+  pub farm: Account<'info, Farm>,
+  pub reward_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Farm's token accounts
+  #[account(
+    init,
+    payer = authority,
+    token::mint = lpt_mint,
+    token::authority = treasurer,
+    token::token_program = pool_token_program,
+    seeds = [b"farm_vault".as_ref(), &farm.key().to_bytes()], bump
+  )]
+  pub vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init,
+    payer = authority,
+    token::mint = reward_mint,
+    token::authority = treasurer,
+    token::token_program = reward_token_program,
+    seeds = [b"farm_reward_vault".as_ref(), &farm.key().to_bytes()], bump
+  )]
+  pub reward_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    mut,
+    associated_token::mint = reward_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = reward_token_program
+  )]
+  pub reward_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub reward_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(
+  ctx: Context<InitializeFarm>,
+  emission_rate: u64,
+  start_time: i64,
+  end_time: i64,
+  reward_amount: u64,
+  early_exit_penalty: u64,
+) -> Result<()> {
+  if !(emission_rate > 0 && reward_amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  if early_exit_penalty > PRECISION_U64 {
+    return err!(ErrorCode::InvalidFee);
+  }
+  if !(start_time < end_time) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  // Transfer Reward, only what the vault received is paid out
+  let reward_amount = transfer_in(
+    &ctx.accounts.reward_token_program,
+    ctx.accounts.reward_token_account.to_account_info(),
+    &mut ctx.accounts.reward_vault,
+    &ctx.accounts.reward_mint,
+    ctx.accounts.authority.to_account_info(),
+    &[],
+    reward_amount,
+  )?;
+  let farm = &mut ctx.accounts.farm;
+  farm.authority = ctx.accounts.authority.key();
+  farm.pool = ctx.accounts.pool.key();
+  farm.reward_mint = ctx.accounts.reward_mint.key();
+  farm.vault = ctx.accounts.vault.key();
+  farm.reward_vault = ctx.accounts.reward_vault.key();
+  farm.emission_rate = emission_rate;
+  farm.start_time = start_time;
+  farm.end_time = end_time;
+  farm.last_reward_time = start_time;
+  farm.reward_per_share = 0;
+  farm.total_staked = 0;
+  farm.total_reward = reward_amount;
+  farm.early_exit_penalty = early_exit_penalty;
+  // The whole emission schedule must be funded upfront
+  let total_emission = farm
+    .calc_total_emission(end_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if total_emission > reward_amount {
+    return err!(ErrorCode::InsufficientReward);
+  }
+  let pool = &mut ctx.accounts.pool;
+  pool.open_farms = pool
+    .open_farms
+    .checked_add(1)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(())
+}
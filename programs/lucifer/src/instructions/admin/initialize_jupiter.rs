@@ -2,7 +2,7 @@ use crate::constant::*;
 use crate::schema::*;
 
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 
 // Test for Devnet
 #[derive(Accounts)]
@@ -23,13 +23,14 @@ pub struct InitializeJupiter<'info> {
     mint::decimals = MINT_LPT_DECIMALS,
     mint::authority = treasurer,
     mint::freeze_authority = treasurer,
+    mint::token_program = base_token_program,
     seeds = [b"base_mint".as_ref(), &jupiter.key().to_bytes()], bump
   )]
-  pub base_mint: Account<'info, token::Mint>,
+  pub base_mint: InterfaceAccount<'info, token_interface::Mint>,
 
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
@@ -37,5 +38,6 @@ pub struct InitializeJupiter<'info> {
 pub fn exec(ctx: Context<InitializeJupiter>) -> Result<()> {
   let jupiter = &mut ctx.accounts.jupiter;
   jupiter.base_mint = ctx.accounts.base_mint.key();
+  jupiter.version = Jupiter::VERSION;
   Ok(())
 }
@@ -5,9 +5,16 @@ use crate::oracle::*;
 use crate::schema::*;
 
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 
 #[derive(Accounts)]
+#[instruction(
+  fee: u64,
+  amount: u64,
+  stable_amount: u64,
+  base_amount: u64,
+  stable_decimals: u8
+)]
 pub struct InitializePool<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
@@ -18,83 +25,94 @@ pub struct InitializePool<'info> {
   /// CHECK: Just a pure account
   pub treasurer: AccountInfo<'info>,
   // Pool's Mints
-  pub mint: Box<Account<'info, token::Mint>>,
-  pub base_mint: Box<Account<'info, token::Mint>>,
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(constraint = base_mint.key() != mint.key() @ ErrorCode::InvalidMint)]
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
   #[account(
     init,
     payer = authority,
-    mint::decimals = MINT_LPT_DECIMALS,
+    mint::decimals = stable_decimals,
     mint::authority = treasurer,
     mint::freeze_authority = treasurer,
+    mint::token_program = pool_token_program,
     seeds = [b"stable_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub stable_mint: Account<'info, token::Mint>,
+  pub stable_mint: InterfaceAccount<'info, token_interface::Mint>,
   #[account(
     init,
     payer = authority,
     mint::decimals = MINT_LPT_DECIMALS,
     mint::authority = treasurer,
     mint::freeze_authority = treasurer,
+    mint::token_program = pool_token_program,
     seeds = [b"lpt_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub lpt_mint: Account<'info, token::Mint>,
+  pub lpt_mint: InterfaceAccount<'info, token_interface::Mint>,
   // Pool's token account
   #[account(
     init,
     payer = authority,
     associated_token::mint = mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
   )]
-  pub treasury: Box<Account<'info, token::TokenAccount>>,
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init,
     payer = authority,
     associated_token::mint = stable_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
   )]
-  pub base_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init,
     payer = authority,
     associated_token::mint = lpt_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Wallet's Token Accounts
   #[account(
     mut,
     associated_token::mint = mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
   )]
-  pub token_account: Box<Account<'info, token::TokenAccount>>,
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = stable_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
   )]
-  pub base_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init,
     payer = authority,
     associated_token::mint = lpt_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
   #[account(
     init, 
@@ -106,7 +124,10 @@ pub struct InitializePool<'info> {
 
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program the stable and LP mints are created under, the token program or Token-2022
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
@@ -117,8 +138,12 @@ pub fn exec(
   amount: u64,
   stable_amount: u64,
   base_amount: u64,
+  stable_decimals: u8,
 ) -> Result<()> {
-  let current_time = current_timestamp().unwrap();
+  if fee > PRECISION_U64 {
+    return err!(ErrorCode::InvalidFee);
+  }
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
   let pool = &mut ctx.accounts.pool;
   let cert = &mut ctx.accounts.cert;
   pool.authority = ctx.accounts.authority.key();
@@ -127,78 +152,117 @@ pub fn exec(
   pool.stable_mint = ctx.accounts.stable_mint.key();
   pool.base_mint = ctx.accounts.base_mint.key();
   pool.lpt_mint = ctx.accounts.lpt_mint.key();
+  // Stable decimals, the same as the base by default
+  let base_decimals = ctx.accounts.base_mint.decimals;
+  if stable_decimals.max(base_decimals) - stable_decimals.min(base_decimals) > MAX_DECIMALS_DIFF {
+    return err!(ErrorCode::InvalidDecimals);
+  }
+  pool.base_decimals = base_decimals;
+  pool.stable_decimals = stable_decimals;
 
   if !(amount > 0 && stable_amount > 0) {
     return err!(ErrorCode::AmountZero);
   }
   // WALLET ACTIONS
-  // Transfer Mint
-  token::transfer(
-    CpiContext::new(
-      ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
-        from: ctx.accounts.token_account.to_account_info(),
-        to: ctx.accounts.treasury.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
-      },
-    ),
+  // Transfer Mint, the pool starts with what the treasuries received
+  let amount = transfer_in(
+    &ctx.accounts.token_program,
+    ctx.accounts.token_account.to_account_info(),
+    &mut ctx.accounts.treasury,
+    &ctx.accounts.mint,
+    ctx.accounts.authority.to_account_info(),
+    &[],
     amount,
   )?;
   // Transfer Base Mint
-  if base_amount > 0 {
-    token::transfer(
-      CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
-          from: ctx.accounts.base_token_account.to_account_info(),
-          to: ctx.accounts.base_treasury.to_account_info(),
-          authority: ctx.accounts.authority.to_account_info(),
-        },
-      ),
+  let base_amount = if base_amount > 0 {
+    transfer_in(
+      &ctx.accounts.base_token_program,
+      ctx.accounts.base_token_account.to_account_info(),
+      &mut ctx.accounts.base_treasury,
+      &ctx.accounts.base_mint,
+      ctx.accounts.authority.to_account_info(),
+      &[],
       base_amount,
-    )?;
+    )?
+  } else {
+    0
+  };
+  if amount < MINIMUM_RESERVE || stable_amount < MINIMUM_RESERVE {
+    return err!(ErrorCode::InsufficientInitialLiquidity);
+  }
+  // Stable is only minted against deposited base
+  let base_stable_amount = pool
+    .calc_stable_amount(base_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if base_stable_amount < stable_amount {
+    return err!(ErrorCode::UnbackedStable);
   }
   // POOL ACTIONS
   // Mint to stable token
   let seeds: &[&[&[u8]]] = &[&[
     "treasurer".as_ref(),
     &pool.key().to_bytes(),
-    &[*ctx.bumps.get("treasurer").unwrap()],
+    &[ctx.bumps.treasurer],
   ]];
 
   let mint_to_stable = CpiContext::new_with_signer(
-    ctx.accounts.token_program.to_account_info(),
-    token::MintTo {
+    ctx.accounts.pool_token_program.to_account_info(),
+    token_interface::MintTo {
       to: ctx.accounts.stable_treasury.to_account_info(),
       mint: ctx.accounts.stable_mint.to_account_info(),
       authority: ctx.accounts.treasurer.to_account_info(),
     },
     seeds,
   );
-  token::mint_to(mint_to_stable, stable_amount)?;
-
-  let mint_to_stable = CpiContext::new_with_signer(
-    ctx.accounts.token_program.to_account_info(),
-    token::MintTo {
-      to: ctx.accounts.stable_token_account.to_account_info(),
-      mint: ctx.accounts.stable_mint.to_account_info(),
+  token_interface::mint_to(mint_to_stable, stable_amount)?;
+  // The rest of the base goes back to the authority as stable
+  let stable_change = base_stable_amount
+    .checked_sub(stable_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if stable_change > 0 {
+    let mint_to_stable = CpiContext::new_with_signer(
+      ctx.accounts.pool_token_program.to_account_info(),
+      token_interface::MintTo {
+        to: ctx.accounts.stable_token_account.to_account_info(),
+        mint: ctx.accounts.stable_mint.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    );
+    token_interface::mint_to(mint_to_stable, stable_change)?;
+  }
+  // Mint to LPT
+  let lpt_amount = calc_starting_lpt(amount, stable_amount).ok_or(ErrorCode::MathOverflow)?;
+  if lpt_amount <= MINIMUM_LIQUIDITY {
+    return err!(ErrorCode::InsufficientInitialLiquidity);
+  }
+  // The minimum liquidity stays in the treasury forever so the share price
+  // can't be inflated by the first depositor
+  let mint_to_ctx = CpiContext::new_with_signer(
+    ctx.accounts.pool_token_program.to_account_info(),
+    token_interface::MintTo {
+      to: ctx.accounts.lpt_treasury.to_account_info(),
+      mint: ctx.accounts.lpt_mint.to_account_info(),
       authority: ctx.accounts.treasurer.to_account_info(),
     },
     seeds,
   );
-  token::mint_to(mint_to_stable, stable_amount)?;
-  // Mint to LPT
-  let lpt_amount = calc_starting_lpt(amount, stable_amount).unwrap();
+  token_interface::mint_to(mint_to_ctx, MINIMUM_LIQUIDITY)?;
+  // The rest belongs to the creator
+  let creator_lpt_amount = lpt_amount
+    .checked_sub(MINIMUM_LIQUIDITY)
+    .ok_or(ErrorCode::MathOverflow)?;
   let mint_to_ctx = CpiContext::new_with_signer(
-    ctx.accounts.token_program.to_account_info(),
-    token::MintTo {
-      to: ctx.accounts.lpt_treasury.to_account_info(),
+    ctx.accounts.pool_token_program.to_account_info(),
+    token_interface::MintTo {
+      to: ctx.accounts.lpt_token_account.to_account_info(),
       mint: ctx.accounts.lpt_mint.to_account_info(),
       authority: ctx.accounts.treasurer.to_account_info(),
     },
     seeds,
   );
-  token::mint_to(mint_to_ctx, lpt_amount)?;
+  token_interface::mint_to(mint_to_ctx, creator_lpt_amount)?;
   msg!("starting_lpt_amount {}", lpt_amount);
   // Update pool balance
   pool.balance = amount;
@@ -206,12 +270,23 @@ pub fn exec(
   pool.base_balance = base_amount;
   pool.fee = fee;
   pool.total_lpt_fee = fee;
-  pool.lpt_supply = lpt_amount ;
+  pool.lpt_supply = lpt_amount;
   pool.start_time = current_time;
+  pool.version = Pool::VERSION;
   // Update Cert
   cert.authority = ctx.accounts.authority.key();
   cert.pool = pool.key();
-  cert.amount = calc_starting_lpt(amount, base_amount).unwrap();
+  cert.version = Cert::VERSION;
+  cert.amount = creator_lpt_amount;
   msg!("cert.amount {}", cert.amount);
+  check_invariants(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.treasury,
+    &mut ctx.accounts.stable_treasury,
+    &mut ctx.accounts.base_treasury,
+    &mut ctx.accounts.stable_mint,
+    // The treasuries were created empty by this instruction
+    (0, 0, 0),
+  )?;
   Ok(())
 }
@@ -0,0 +1,48 @@
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+
+#[derive(Accounts)]
+#[instruction(decimals: u8)]
+pub struct InitializeProtocol<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Protocol's info, also the authority of the protocol stable
+  #[account(
+    init,
+    payer = authority,
+    space = Protocol::LEN,
+    seeds = [b"protocol".as_ref()], bump
+  )]
+  pub protocol: Account<'info, Protocol>,
+  #[account(
+    init,
+    payer = authority,
+    mint::decimals = decimals,
+    mint::authority = protocol,
+    mint::freeze_authority = protocol,
+    mint::token_program = token_program,
+    seeds = [b"protocol_stable_mint".as_ref()], bump
+  )]
+  pub stable_mint: InterfaceAccount<'info, token_interface::Mint>,
+  // Only the upgrade authority of the program can create the protocol
+  #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+  pub program: Program<'info, crate::program::Lucifer>,
+  #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()))]
+  pub program_data: Account<'info, ProgramData>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<InitializeProtocol>, decimals: u8) -> Result<()> {
+  let protocol = &mut ctx.accounts.protocol;
+  protocol.authority = ctx.accounts.authority.key();
+  protocol.stable_mint = ctx.accounts.stable_mint.key();
+  protocol.decimals = decimals;
+  protocol.total_minted = 0;
+  Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigrateCert<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  #[account(mut, owner = crate::ID)]
+  /// CHECK: Old layouts are shorter than Cert::LEN, deserialized after the realloc
+  pub cert: AccountInfo<'info>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+}
+
+pub fn exec(ctx: Context<MigrateCert>) -> Result<()> {
+  let account = &ctx.accounts.cert;
+  let mut cert = migrate_account::<Cert>(
+    account,
+    &ctx.accounts.payer,
+    &ctx.accounts.system_program,
+    Cert::LEN,
+  )?;
+  if cert.version < Cert::VERSION {
+    msg!("Migrate cert from version {}", cert.version);
+    cert.version = Cert::VERSION;
+    cert.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+  }
+  Ok(())
+}
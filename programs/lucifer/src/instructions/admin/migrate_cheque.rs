@@ -0,0 +1,131 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigrateCheque<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  // Pool's info, migrated before its cheques
+  #[account(mut)]
+  pub pool: Account<'info, Pool>,
+  #[account(mut, owner = crate::ID)]
+  /// CHECK: Old layouts are shorter than Cheque::LEN, deserialized after the realloc
+  pub cheque: AccountInfo<'info>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+}
+
+pub fn exec(ctx: Context<MigrateCheque>) -> Result<()> {
+  let pool = &mut ctx.accounts.pool;
+  if pool.version < Pool::VERSION {
+    return err!(ErrorCode::PoolNotMigrated);
+  }
+  let account = &ctx.accounts.cheque;
+  let mut cheque = migrate_account::<Cheque>(
+    account,
+    &ctx.accounts.payer,
+    &ctx.accounts.system_program,
+    Cheque::LEN,
+  )?;
+  if cheque.pool != pool.key() {
+    return Err(anchor_lang::error::ErrorCode::ConstraintHasOne.into());
+  }
+  if cheque.version < Cheque::VERSION {
+    msg!("Migrate cheque from version {}", cheque.version);
+    // The pool only learns about loans of unversioned cheques here
+    pool
+      .add_legacy_loan(cheque.base_amount)
+      .ok_or(ErrorCode::MathOverflow)?;
+    cheque.version = Cheque::VERSION;
+    cheque.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::constant::*;
+  use crate::test_utils::*;
+  use anchor_lang::error::ErrorCode as AnchorErrorCode;
+
+  // Cheque of the first release: 2 pubkeys and room for 3 u64
+  const BASELINE_LEN: usize = ACCOUNT_DISCRIMINATOR + PUBLIC_KEY_SIZE * 2 + U64_SIZE * 3;
+
+  fn baseline_cheque_info(pool: Pubkey, base_amount: u64) -> AccountInfo<'static> {
+    let cheque = Cheque {
+      authority: Pubkey::new_unique(),
+      pool,
+      borrow_amount: 100,
+      base_amount,
+      version: 0,
+      reserved: [0; 64],
+    };
+    let mut data = Vec::new();
+    cheque.try_serialize(&mut data).unwrap();
+    data.truncate(BASELINE_LEN);
+    reallocable_account_info(Pubkey::new_unique(), crate::ID, data)
+  }
+
+  fn migrate(fixture: &PoolFixture, cheque: &AccountInfo<'static>) -> Result<Pool> {
+    stub_sysvars();
+    let payer = leak_info(signer_info(Pubkey::new_unique()));
+    let pool = leak_info(fixture.pool_info());
+    let system_program = leak_info(program_info(System::id()));
+    let mut accounts = MigrateCheque {
+      payer: Signer::try_from(payer)?,
+      pool: Account::try_from(pool)?,
+      cheque: cheque.clone(),
+      system_program: Program::try_from(system_program)?,
+    };
+    exec(Context::new(
+      &crate::ID,
+      &mut accounts,
+      &[],
+      MigrateChequeBumps::default(),
+    ))?;
+    Ok(accounts.pool.into_inner())
+  }
+
+  #[test]
+  fn adds_unversioned_loans_to_the_pool() {
+    let mut fixture = PoolFixture::new();
+    let cheque = baseline_cheque_info(fixture.key, 400);
+    let pool = migrate(&fixture, &cheque).unwrap();
+    assert_eq!(pool.borrowed, 400);
+    assert_eq!(pool.legacy_borrowed, 400);
+    assert_eq!(cheque.data_len(), Cheque::LEN);
+    let migrated = Cheque::try_deserialize(&mut &cheque.try_borrow_data().unwrap()[..]).unwrap();
+    assert_eq!(migrated.version, Cheque::VERSION);
+    assert_eq!(migrated.base_amount, 400);
+    // A migrated cheque is counted once
+    fixture.pool = pool;
+    let pool = migrate(&fixture, &cheque).unwrap();
+    assert_eq!(pool.borrowed, 400);
+  }
+
+  #[test]
+  fn waits_for_the_pool_migration() {
+    let mut fixture = PoolFixture::new();
+    fixture.pool.version = 0;
+    let cheque = baseline_cheque_info(fixture.key, 400);
+    assert_eq!(
+      error_code(migrate(&fixture, &cheque)),
+      Some(ErrorCode::PoolNotMigrated.into())
+    );
+  }
+
+  #[test]
+  fn rejects_cheques_of_another_pool() {
+    let fixture = PoolFixture::new();
+    let cheque = baseline_cheque_info(Pubkey::new_unique(), 400);
+    assert_eq!(
+      error_code(migrate(&fixture, &cheque)),
+      Some(AnchorErrorCode::ConstraintHasOne.into())
+    );
+  }
+}
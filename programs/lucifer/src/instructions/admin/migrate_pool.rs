@@ -0,0 +1,32 @@
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  #[account(mut, owner = crate::ID)]
+  /// CHECK: Old layouts are shorter than Pool::LEN, deserialized after the realloc
+  pub pool: AccountInfo<'info>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+}
+
+pub fn exec(ctx: Context<MigratePool>) -> Result<()> {
+  let account = &ctx.accounts.pool;
+  let mut pool = migrate_account::<Pool>(
+    account,
+    &ctx.accounts.payer,
+    &ctx.accounts.system_program,
+    Pool::LEN,
+  )?;
+  if pool.version < Pool::VERSION {
+    msg!("Migrate pool from version {}", pool.version);
+    pool.migrate();
+    pool.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+  }
+  Ok(())
+}
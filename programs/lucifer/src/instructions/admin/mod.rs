@@ -6,3 +6,75 @@ pub use initialize_jupiter::*;
 
 pub mod swap_jupiter;
 pub use swap_jupiter::*;
+
+pub mod initialize_farm;
+pub use initialize_farm::*;
+
+pub mod fund_farm;
+pub use fund_farm::*;
+
+pub mod close_farm;
+pub use close_farm::*;
+
+pub mod update_flash_loan_fee;
+pub use update_flash_loan_fee::*;
+
+pub mod update_curve;
+pub use update_curve::*;
+
+pub mod ramp_amp;
+pub use ramp_amp::*;
+
+pub mod update_dynamic_fee;
+pub use update_dynamic_fee::*;
+
+pub mod update_oracle;
+pub use update_oracle::*;
+
+pub mod update_circuit_breaker;
+pub use update_circuit_breaker::*;
+
+pub mod update_referral_fee;
+pub use update_referral_fee::*;
+
+pub mod update_fee_tiers;
+pub use update_fee_tiers::*;
+
+pub mod set_fee_allowance;
+pub use set_fee_allowance::*;
+
+pub mod revoke_fee_allowance;
+pub use revoke_fee_allowance::*;
+
+pub mod update_pool_status;
+pub use update_pool_status::*;
+
+pub mod close_pool;
+pub use close_pool::*;
+
+pub mod migrate_pool;
+pub use migrate_pool::*;
+
+pub mod migrate_cheque;
+pub use migrate_cheque::*;
+
+pub mod migrate_cert;
+pub use migrate_cert::*;
+
+pub mod skim;
+pub use skim::*;
+
+pub mod fund_lending_reserve;
+pub use fund_lending_reserve::*;
+
+pub mod withdraw_lending_reserve;
+pub use withdraw_lending_reserve::*;
+
+pub mod initialize_protocol;
+pub use initialize_protocol::*;
+
+pub mod set_debt_ceiling;
+pub use set_debt_ceiling::*;
+
+pub mod update_vault_config;
+pub use update_vault_config::*;
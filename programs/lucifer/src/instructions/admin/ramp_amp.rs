@@ -0,0 +1,37 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RampAmp<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub pool: Account<'info, Pool>,
+}
+
+pub fn exec(ctx: Context<RampAmp>, amp_target: u64, end_time: i64) -> Result<()> {
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let pool = &mut ctx.accounts.pool;
+  if pool.curve != CurveType::StableSwap || !(MIN_AMP..=MAX_AMP).contains(&amp_target) {
+    return err!(ErrorCode::InvalidAmp);
+  }
+  if end_time < current_time.saturating_add(MIN_RAMP_DURATION) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  // Ramp from wherever the current ramp is
+  let amp = pool.calc_amp(current_time).ok_or(ErrorCode::MathOverflow)?;
+  let max_amp = amp
+    .checked_mul(MAX_AMP_CHANGE)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if amp_target > max_amp || amp_target.saturating_mul(MAX_AMP_CHANGE) < amp {
+    return err!(ErrorCode::InvalidAmp);
+  }
+  pool.amp_initial = amp;
+  pool.amp_target = amp_target;
+  pool.amp_start_time = current_time;
+  pool.amp_end_time = end_time;
+  Ok(())
+}
@@ -0,0 +1,17 @@
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RevokeFeeAllowance<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  #[account(has_one = authority)]
+  pub pool: Account<'info, Pool>,
+  #[account(mut, close = authority, has_one = pool)]
+  pub fee_allowance: Account<'info, FeeAllowance>,
+}
+
+pub fn exec(_ctx: Context<RevokeFeeAllowance>) -> Result<()> {
+  Ok(())
+}
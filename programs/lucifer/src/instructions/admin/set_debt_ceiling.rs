@@ -0,0 +1,53 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+
+#[derive(Accounts)]
+pub struct SetDebtCeiling<'info> {
+  // Protocol's authority
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  #[account(has_one = authority, seeds = [b"protocol".as_ref()], bump)]
+  pub protocol: Account<'info, Protocol>,
+  // The pool opts in with its own authority
+  pub pool_authority: Signer<'info>,
+  #[account(
+    constraint = pool.authority == pool_authority.key(),
+    has_one = base_mint
+  )]
+  pub pool: Account<'info, Pool>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(seeds = [b"vault_config".as_ref(), &pool.key().to_bytes()], bump)]
+  /// CHECK: Must be empty, a vault config means the base is priced by an oracle
+  pub vault_config: AccountInfo<'info>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = StableMember::LEN,
+    seeds = [b"stable_member".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub stable_member: Account<'info, StableMember>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+// A ceiling below the minted share only stops new mints, burns are always allowed
+pub fn exec(ctx: Context<SetDebtCeiling>, debt_ceiling: u64) -> Result<()> {
+  // Pools created before the decimals were stored can't be scaled
+  if ctx.accounts.pool.base_decimals != ctx.accounts.base_mint.decimals {
+    return err!(ErrorCode::InvalidDecimals);
+  }
+  // The protocol stable mints 1:1 against the base
+  if !ctx.accounts.vault_config.data_is_empty() {
+    return err!(ErrorCode::OraclePricedBase);
+  }
+  let stable_member = &mut ctx.accounts.stable_member;
+  stable_member.protocol = ctx.accounts.protocol.key();
+  stable_member.pool = ctx.accounts.pool.key();
+  stable_member.debt_ceiling = debt_ceiling;
+  Ok(())
+}
@@ -0,0 +1,38 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetFeeAllowance<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  #[account(has_one = authority)]
+  pub pool: Account<'info, Pool>,
+  /// CHECK: Just a pure account
+  pub wallet: AccountInfo<'info>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = FeeAllowance::LEN,
+    seeds = [b"fee_allowance".as_ref(), &pool.key().to_bytes(), &wallet.key().to_bytes()],
+    bump
+  )]
+  pub fee_allowance: Account<'info, FeeAllowance>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<SetFeeAllowance>, fee: u64) -> Result<()> {
+  if fee > PRECISION_U64 {
+    return err!(ErrorCode::InvalidFee);
+  }
+  let fee_allowance = &mut ctx.accounts.fee_allowance;
+  fee_allowance.pool = ctx.accounts.pool.key();
+  fee_allowance.wallet = ctx.accounts.wallet.key();
+  fee_allowance.fee = fee;
+  Ok(())
+}
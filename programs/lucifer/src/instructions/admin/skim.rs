@@ -0,0 +1,164 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct SkimPool<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(
+    has_one = authority,
+    has_one = mint,
+    has_one = stable_mint,
+    has_one = base_mint
+  )]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
+  )]
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = stable_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = base_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Recipient's Token Accounts
+  /// CHECK: Just a pure account
+  pub recipient: AccountInfo<'info>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = mint,
+    associated_token::authority = recipient,
+    associated_token::token_program = token_program
+  )]
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = stable_mint,
+    associated_token::authority = recipient,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = base_mint,
+    associated_token::authority = recipient,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+// Send whatever the treasuries hold above the ledgers to the recipient
+pub fn exec(ctx: Context<SkimPool>) -> Result<()> {
+  let pool = &ctx.accounts.pool;
+  if pool.flash_loan_amount > 0 {
+    return err!(ErrorCode::FlashLoanInProgress);
+  }
+  let (surplus, stable_surplus, base_surplus) = calc_surpluses(
+    pool,
+    &ctx.accounts.treasury,
+    &ctx.accounts.stable_treasury,
+    &ctx.accounts.base_treasury,
+  )?;
+  // Pool signature
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  for (token_program, mint, from, to, surplus) in [
+    (
+      ctx.accounts.token_program.to_account_info(),
+      &ctx.accounts.mint,
+      ctx.accounts.treasury.to_account_info(),
+      ctx.accounts.token_account.to_account_info(),
+      surplus,
+    ),
+    (
+      ctx.accounts.pool_token_program.to_account_info(),
+      &ctx.accounts.stable_mint,
+      ctx.accounts.stable_treasury.to_account_info(),
+      ctx.accounts.stable_token_account.to_account_info(),
+      stable_surplus,
+    ),
+    (
+      ctx.accounts.base_token_program.to_account_info(),
+      &ctx.accounts.base_mint,
+      ctx.accounts.base_treasury.to_account_info(),
+      ctx.accounts.base_token_account.to_account_info(),
+      base_surplus,
+    ),
+  ] {
+    if !(surplus > 0) {
+      continue;
+    }
+    token_interface::transfer_checked(
+      CpiContext::new_with_signer(
+        token_program,
+        token_interface::TransferChecked {
+          from,
+          mint: mint.to_account_info(),
+          to,
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      surplus,
+      mint.decimals,
+    )?;
+  }
+  emit!(SkimEvent {
+    pool: pool.key(),
+    recipient: ctx.accounts.recipient.key(),
+    amount: surplus,
+    stable_amount: stable_surplus,
+    base_amount: base_surplus,
+  });
+  check_invariants(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.treasury,
+    &mut ctx.accounts.stable_treasury,
+    &mut ctx.accounts.base_treasury,
+    &mut ctx.accounts.stable_mint,
+    // Nothing is left above the ledgers
+    (0, 0, 0),
+  )?;
+  Ok(())
+}
@@ -1,6 +1,6 @@
 use crate::schema::*;
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 
 // Only Test Devnet
 // Wait implement Jupiter Mainnet
@@ -20,35 +20,39 @@ pub struct SwapJupiter<'info> {
     mut,
     seeds = [b"base_mint".as_ref(), &jupiter.key().to_bytes()], bump
   )]
-  pub base_mint: Account<'info, token::Mint>,
-  pub mint: Account<'info, token::Mint>,
+  pub base_mint: InterfaceAccount<'info, token_interface::Mint>,
+  pub mint: InterfaceAccount<'info, token_interface::Mint>,
 
   #[account(
     init_if_needed,
     payer=authority,
     associated_token::mint = mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
   )]
-  pub mint_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub mint_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
   )]
-  pub token_account: Box<Account<'info, token::TokenAccount>>,
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
   )]
-  pub base_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
@@ -59,33 +63,35 @@ pub fn exec(ctx: Context<SwapJupiter>, amount_in: u64, base_amount: u64) -> Resu
   let seeds: &[&[&[u8]]] = &[&[
     "treasurer".as_ref(),
     &jupiter.key().to_bytes(),
-    &[*ctx.bumps.get("treasurer").unwrap()],
+    &[ctx.bumps.treasurer],
   ]];
 
   if amount_in > 0 {
-    token::transfer(
+    token_interface::transfer_checked(
       CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
+        token_interface::TransferChecked {
           from: ctx.accounts.token_account.to_account_info(),
+          mint: ctx.accounts.mint.to_account_info(),
           to: ctx.accounts.mint_treasury.to_account_info(),
           authority: ctx.accounts.authority.to_account_info(),
         },
       ),
       amount_in,
+      ctx.accounts.mint.decimals,
     )?;
   }
 
   let mint_to_stable = CpiContext::new_with_signer(
-    ctx.accounts.token_program.to_account_info(),
-    token::MintTo {
+    ctx.accounts.base_token_program.to_account_info(),
+    token_interface::MintTo {
       to: ctx.accounts.base_token_account.to_account_info(),
       mint: ctx.accounts.base_mint.to_account_info(),
       authority: ctx.accounts.treasurer.to_account_info(),
     },
     seeds,
   );
-  token::mint_to(mint_to_stable, base_amount)?;
+  token_interface::mint_to(mint_to_stable, base_amount)?;
 
   Ok(())
 }
@@ -0,0 +1,33 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateCircuitBreaker<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub pool: Account<'info, Pool>,
+}
+
+// Zero limits disable the checks, updating also lifts an ongoing halt
+pub fn exec(
+  ctx: Context<UpdateCircuitBreaker>,
+  max_price_impact: u64,
+  max_window_move: u64,
+  window_duration: i64,
+  halt_duration: i64,
+) -> Result<()> {
+  if max_window_move > 0 && !(window_duration > 0 && halt_duration > 0) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  let pool = &mut ctx.accounts.pool;
+  pool.max_price_impact = max_price_impact;
+  pool.max_window_move = max_window_move;
+  pool.window_duration = window_duration;
+  pool.halt_duration = halt_duration;
+  pool.window_start_time = 0;
+  pool.window_start_price = 0;
+  pool.halted_until = 0;
+  Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateCurve<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub pool: Account<'info, Pool>,
+}
+
+pub fn exec(ctx: Context<UpdateCurve>, curve: CurveType, amp: u64) -> Result<()> {
+  if curve == CurveType::StableSwap && !(MIN_AMP..=MAX_AMP).contains(&amp) {
+    return err!(ErrorCode::InvalidAmp);
+  }
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let pool = &mut ctx.accounts.pool;
+  pool.curve = curve;
+  pool.amp_initial = amp;
+  pool.amp_target = amp;
+  pool.amp_start_time = current_time;
+  pool.amp_end_time = current_time;
+  Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateDynamicFee<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub pool: Account<'info, Pool>,
+}
+
+pub fn exec(
+  ctx: Context<UpdateDynamicFee>,
+  dynamic_fee: bool,
+  min_fee: u64,
+  max_fee: u64,
+  volatility_half_life: i64,
+) -> Result<()> {
+  if !(min_fee <= max_fee && max_fee <= PRECISION_U64) {
+    return err!(ErrorCode::InvalidFee);
+  }
+  if dynamic_fee && !(volatility_half_life > 0) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let pool = &mut ctx.accounts.pool;
+  pool.dynamic_fee = dynamic_fee;
+  pool.min_fee = min_fee;
+  pool.max_fee = max_fee;
+  pool.volatility_half_life = volatility_half_life;
+  // Restart measuring from the current price
+  pool.last_price = pool.calc_spot_price().ok_or(ErrorCode::MathOverflow)?;
+  pool.volatility = 0;
+  pool.volatility_updated_at = current_time;
+  Ok(())
+}
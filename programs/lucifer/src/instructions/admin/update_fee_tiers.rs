@@ -0,0 +1,41 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+
+#[derive(Accounts)]
+pub struct UpdateFeeTiers<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  #[account(has_one = authority)]
+  pub pool: Account<'info, Pool>,
+  pub gov_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = FeeTiers::LEN,
+    seeds = [b"fee_tiers".as_ref(), &pool.key().to_bytes()],
+    bump
+  )]
+  pub fee_tiers: Account<'info, FeeTiers>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<UpdateFeeTiers>, tiers: Vec<FeeTier>) -> Result<()> {
+  if tiers.len() > MAX_FEE_TIERS {
+    return err!(ErrorCode::TooManyFeeTiers);
+  }
+  if tiers.iter().any(|tier| tier.fee > PRECISION_U64) {
+    return err!(ErrorCode::InvalidFee);
+  }
+  let fee_tiers = &mut ctx.accounts.fee_tiers;
+  fee_tiers.pool = ctx.accounts.pool.key();
+  fee_tiers.gov_mint = ctx.accounts.gov_mint.key();
+  fee_tiers.tiers = tiers;
+  Ok(())
+}
@@ -0,0 +1,21 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateFlashLoanFee<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub pool: Account<'info, Pool>,
+}
+
+pub fn exec(ctx: Context<UpdateFlashLoanFee>, flash_loan_fee: u64) -> Result<()> {
+  if flash_loan_fee > PRECISION_U64 {
+    return err!(ErrorCode::InvalidFee);
+  }
+  let pool = &mut ctx.accounts.pool;
+  pool.flash_loan_fee = flash_loan_fee;
+  Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateOracle<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub pool: Account<'info, Pool>,
+  /// CHECK: Parsed as a Pyth-compatible price account
+  pub oracle: AccountInfo<'info>,
+}
+
+// A zero band disables the guard
+pub fn exec(ctx: Context<UpdateOracle>, oracle_band: u64, oracle_max_age: i64) -> Result<()> {
+  let pool = &mut ctx.accounts.pool;
+  if !(oracle_band > 0) {
+    pool.oracle = Pubkey::default();
+    pool.oracle_band = 0;
+    pool.oracle_max_age = 0;
+    return Ok(());
+  }
+  if !(oracle_max_age > 0) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  let oracle = &ctx.accounts.oracle;
+  PriceFeed::parse(&oracle.try_borrow_data()?).ok_or(ErrorCode::InvalidOracle)?;
+  pool.oracle = oracle.key();
+  pool.oracle_band = oracle_band;
+  pool.oracle_max_age = oracle_max_age;
+  Ok(())
+}
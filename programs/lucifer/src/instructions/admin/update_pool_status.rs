@@ -0,0 +1,16 @@
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolStatus<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub pool: Account<'info, Pool>,
+}
+
+pub fn exec(ctx: Context<UpdatePoolStatus>, status: PoolStatus) -> Result<()> {
+  let pool = &mut ctx.accounts.pool;
+  pool.status = status;
+  Ok(())
+}
@@ -0,0 +1,21 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateReferralFee<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub pool: Account<'info, Pool>,
+}
+
+pub fn exec(ctx: Context<UpdateReferralFee>, referral_fee: u64) -> Result<()> {
+  if referral_fee > PRECISION_U64 {
+    return err!(ErrorCode::InvalidFee);
+  }
+  let pool = &mut ctx.accounts.pool;
+  pool.referral_fee = referral_fee;
+  Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+
+#[derive(Accounts)]
+pub struct UpdateVaultConfig<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  #[account(has_one = authority, has_one = base_mint, has_one = stable_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Vaults mint their own stable, the pool's stable is only ever backed by base
+  #[account(
+    init_if_needed,
+    payer = authority,
+    mint::decimals = stable_mint.decimals,
+    mint::authority = treasurer,
+    mint::freeze_authority = treasurer,
+    mint::token_program = pool_token_program,
+    seeds = [b"vault_stable_mint".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub vault_stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  /// CHECK: Parsed as a Pyth-compatible price account of the base in stable
+  pub oracle: AccountInfo<'info>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = VaultConfig::LEN,
+    seeds = [b"vault_config".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub vault_config: Account<'info, VaultConfig>,
+  #[account(seeds = [b"stable_member".as_ref(), &pool.key().to_bytes()], bump)]
+  /// CHECK: Must be empty, members of the protocol stable mint 1:1 against the base
+  pub stable_member: AccountInfo<'info>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+// Vaults can be liquidated below the liquidation ratio, and new debt needs the higher
+// minimum collateral ratio
+pub fn exec(
+  ctx: Context<UpdateVaultConfig>,
+  oracle_max_age: i64,
+  min_collateral_ratio: u64,
+  liquidation_ratio: u64,
+  liquidation_bonus: u64,
+  stability_fee: u64,
+) -> Result<()> {
+  // Pools created before the decimals were stored can't be valued
+  if ctx.accounts.pool.base_decimals != ctx.accounts.base_mint.decimals {
+    return err!(ErrorCode::InvalidDecimals);
+  }
+  if !ctx.accounts.stable_member.data_is_empty() {
+    return err!(ErrorCode::OraclePricedBase);
+  }
+  if !(oracle_max_age > 0) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  if !(liquidation_ratio >= PRECISION_U64 && min_collateral_ratio >= liquidation_ratio) {
+    return err!(ErrorCode::InvalidCollateralRatio);
+  }
+  let oracle = &ctx.accounts.oracle;
+  PriceFeed::parse(&oracle.try_borrow_data()?).ok_or(ErrorCode::InvalidOracle)?;
+  let vault_config = &mut ctx.accounts.vault_config;
+  vault_config.pool = ctx.accounts.pool.key();
+  vault_config.oracle = oracle.key();
+  vault_config.oracle_max_age = oracle_max_age;
+  vault_config.min_collateral_ratio = min_collateral_ratio;
+  vault_config.liquidation_ratio = liquidation_ratio;
+  vault_config.liquidation_bonus = liquidation_bonus;
+  vault_config.stability_fee = stability_fee;
+  Ok(())
+}
@@ -1,56 +1,60 @@
 use crate::errors::ErrorCode;
 use crate::oracle::*;
 use crate::schema::*;
+use crate::utils::*;
 
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 
 #[derive(Accounts)]
 pub struct Borrow<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
   // Pool's info
-  #[account(mut)]
+  #[account(mut, has_one = base_mint, has_one = lpt_mint)]
   pub pool: Account<'info, Pool>,
   #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
   /// CHECK: Just a pure account
   pub treasurer: AccountInfo<'info>,
   // Pool's Mints
-  #[account(mut)]
-  pub base_mint: Account<'info, token::Mint>,
+  pub base_mint: InterfaceAccount<'info, token_interface::Mint>,
   #[account(
     mut,
     seeds = [b"lpt_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub lpt_mint: Account<'info, token::Mint>,
+  pub lpt_mint: InterfaceAccount<'info, token_interface::Mint>,
   // Pool's token account
   #[account(
     mut,
     associated_token::mint = base_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
   )]
-  pub base_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = lpt_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Wallet's Token Accounts
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
   )]
-  pub base_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = lpt_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Instruction Data
   #[account(
     init_if_needed,
@@ -61,78 +65,166 @@ pub struct Borrow<'info> {
   pub cheque: Account<'info, Cheque>,
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
 
 pub fn exec(ctx: Context<Borrow>, lpt_amount: u64) -> Result<()> {
+  let base_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.base_treasury)?;
   if !(lpt_amount > 0) {
     return err!(ErrorCode::AmountZero);
   }
   let pool = &mut ctx.accounts.pool;
+  check_active(pool)?;
   let cheque = &mut ctx.accounts.cheque;
-  let lpt_amount_fee = pool.calc_fee(lpt_amount).unwrap();
+  let lpt_amount_fee = pool.calc_fee(lpt_amount).ok_or(ErrorCode::MathOverflow)?;
   // Fee
-  let lpt_amount_with_fee = lpt_amount - lpt_amount_fee;
+  let lpt_amount_with_fee = lpt_amount
+    .checked_sub(lpt_amount_fee)
+    .ok_or(ErrorCode::MathOverflow)?;
   let burn_lpt = CpiContext::new(
-    ctx.accounts.token_program.to_account_info(),
-    token::Burn {
+    ctx.accounts.pool_token_program.to_account_info(),
+    token_interface::Burn {
       from: ctx.accounts.lpt_token_account.to_account_info(),
       mint: ctx.accounts.lpt_mint.to_account_info(),
       authority: ctx.accounts.authority.to_account_info(),
     },
   );
-  token::burn(burn_lpt, lpt_amount_fee)?;
-  pool.total_lpt_fee += lpt_amount_fee;
+  token_interface::burn(burn_lpt, lpt_amount_fee)?;
+  pool.total_lpt_fee = pool
+    .total_lpt_fee
+    .checked_add(lpt_amount_fee)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.lpt_supply = pool
+    .lpt_supply
+    .checked_sub(lpt_amount_fee)
+    .ok_or(ErrorCode::MathOverflow)?;
 
   if !(cheque.borrow_amount > 0) {
     cheque.authority = ctx.accounts.authority.key();
     cheque.pool = pool.key();
     cheque.borrow_amount = 0;
     cheque.base_amount = 0;
+    cheque.version = Cheque::VERSION;
   }
-  cheque.borrow_amount += lpt_amount_with_fee;
+  cheque.borrow_amount = cheque
+    .borrow_amount
+    .checked_add(lpt_amount_with_fee)
+    .ok_or(ErrorCode::MathOverflow)?;
 
   // Lock lpt token
-  token::transfer(
+  token_interface::transfer_checked(
     CpiContext::new(
-      ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
+      ctx.accounts.pool_token_program.to_account_info(),
+      token_interface::TransferChecked {
         from: ctx.accounts.lpt_token_account.to_account_info(),
+        mint: ctx.accounts.lpt_mint.to_account_info(),
         to: ctx.accounts.lpt_treasury.to_account_info(),
         authority: ctx.accounts.authority.to_account_info(),
       },
     ),
     lpt_amount_with_fee,
+    ctx.accounts.lpt_mint.decimals,
   )?;
   // Borrow calculate
   let reserves = vec![pool.balance, pool.stable_balance];
   let supply = ctx.accounts.lpt_mint.supply;
-  let amounts =
-    calc_mint_receives_remove_full_side(lpt_amount_with_fee, supply, &reserves).unwrap();
-  let base_amount = amounts[1];
+  let amounts = calc_mint_receives_remove_full_side(lpt_amount_with_fee, supply, &reserves)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let base_amount = pool
+    .calc_base_amount(amounts[1])
+    .ok_or(ErrorCode::MathOverflow)?;
   msg!("Borrow amount {}", base_amount);
   // Update pool info
-  pool.base_balance -= base_amount;
+  // Loans only come out of the lending reserve, never the stable backing
+  pool.lending_reserve = pool
+    .lending_reserve
+    .checked_sub(base_amount)
+    .ok_or(ErrorCode::InsufficientLiquidity)?;
+  pool.borrowed = pool
+    .borrowed
+    .checked_add(base_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
   // Pool actions: transfer base mint
   let seeds: &[&[&[u8]]] = &[&[
     "treasurer".as_ref(),
     &pool.key().to_bytes(),
-    &[*ctx.bumps.get("treasurer").unwrap()],
+    &[ctx.bumps.treasurer],
   ]];
-  token::transfer(
+  token_interface::transfer_checked(
     CpiContext::new_with_signer(
-      ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
+      ctx.accounts.base_token_program.to_account_info(),
+      token_interface::TransferChecked {
         from: ctx.accounts.base_treasury.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
         to: ctx.accounts.base_token_account.to_account_info(),
         authority: ctx.accounts.treasurer.to_account_info(),
       },
       seeds,
     ),
     base_amount,
+    ctx.accounts.base_mint.decimals,
+  )?;
+  cheque.base_amount = cheque
+    .base_amount
+    .checked_add(base_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.base_treasury,
+    base_surplus,
   )?;
-  cheque.base_amount += base_amount;
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+  use anchor_lang::error::ErrorCode as AnchorErrorCode;
+
+  #[test]
+  fn accepts_own_cheque() {
+    let fixture = PoolFixture::new();
+    let authority = Pubkey::new_unique();
+    let cheque = fixture.cheque_info(authority, fixture.key);
+    let accounts = fixture.cheque_accounts(authority, fixture.pool.base_mint, cheque);
+    assert_eq!(constraint_error::<Borrow>(&accounts), None);
+  }
+
+  #[test]
+  fn rejects_foreign_cheques() {
+    let fixture = PoolFixture::new();
+    let other = PoolFixture::new();
+    let authority = Pubkey::new_unique();
+    // A cheque of another pool
+    let cheque = other.cheque_info(authority, other.key);
+    let accounts = fixture.cheque_accounts(authority, fixture.pool.base_mint, cheque);
+    assert_eq!(
+      constraint_error::<Borrow>(&accounts),
+      Some(AnchorErrorCode::ConstraintSeeds.into())
+    );
+    // A cheque of another wallet
+    let cheque = fixture.cheque_info(Pubkey::new_unique(), fixture.key);
+    let accounts = fixture.cheque_accounts(authority, fixture.pool.base_mint, cheque);
+    assert_eq!(
+      constraint_error::<Borrow>(&accounts),
+      Some(AnchorErrorCode::ConstraintSeeds.into())
+    );
+  }
+
+  #[test]
+  fn rejects_foreign_base_mint() {
+    let fixture = PoolFixture::new();
+    let authority = Pubkey::new_unique();
+    let cheque = fixture.cheque_info(authority, fixture.key);
+    let accounts = fixture.cheque_accounts(authority, Pubkey::new_unique(), cheque);
+    assert_eq!(
+      constraint_error::<Borrow>(&accounts),
+      Some(AnchorErrorCode::ConstraintHasOne.into())
+    );
+  }
+}
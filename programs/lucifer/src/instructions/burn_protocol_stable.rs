@@ -0,0 +1,171 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct BurnProtocolStable<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Protocol's info
+  #[account(mut, has_one = stable_mint, seeds = [b"protocol".as_ref()], bump)]
+  pub protocol: Account<'info, Protocol>,
+  #[account(mut)]
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's info
+  #[account(mut, has_one = base_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    mut,
+    has_one = protocol,
+    has_one = pool,
+    seeds = [b"stable_member".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub stable_member: Account<'info, StableMember>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = base_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = base_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = stable_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Temporary wrapped SOL account, a fresh keypair that only signs with native
+  #[account(mut)]
+  /// CHECK: Created and closed within the instruction when native
+  pub wsol_account: AccountInfo<'info>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+// Redeem protocol stable against one pool, up to the share minted at that pool
+pub fn exec(ctx: Context<BurnProtocolStable>, stable_amount: u64, native: bool) -> Result<()> {
+  let base_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.base_treasury)?;
+  if !(stable_amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  // Burn stable mint
+  token_interface::burn(
+    CpiContext::new(
+      ctx.accounts.token_program.to_account_info(),
+      token_interface::Burn {
+        from: ctx.accounts.stable_token_account.to_account_info(),
+        mint: ctx.accounts.stable_mint.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+      },
+    ),
+    stable_amount,
+  )?;
+  msg!("burn protocol stable {}", stable_amount);
+  let protocol = &mut ctx.accounts.protocol;
+  let pool = &mut ctx.accounts.pool;
+  let stable_member = &mut ctx.accounts.stable_member;
+  stable_member.minted = stable_member
+    .minted
+    .checked_sub(stable_amount)
+    .ok_or(ErrorCode::InsufficientLiquidity)?;
+  protocol.total_minted = protocol
+    .total_minted
+    .checked_sub(stable_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // The whole burned amount leaves the backing, the fee moves to the lending reserve
+  let fee_amount = pool
+    .calc_fee(stable_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let stable_amount_after_fee = stable_amount
+    .checked_sub(fee_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let base_amount = protocol
+    .calc_base_amount(stable_amount, pool.base_decimals)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let base_amount_after_fee = protocol
+    .calc_base_amount(stable_amount_after_fee, pool.base_decimals)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let base_fee_amount = base_amount
+    .checked_sub(base_amount_after_fee)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.protocol_backing = pool
+    .protocol_backing
+    .checked_sub(base_amount)
+    .ok_or(ErrorCode::InsufficientLiquidity)?;
+  pool.lending_reserve = pool
+    .lending_reserve
+    .checked_add(base_fee_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Transfer base mint
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  // Pay out as lamports with native
+  let wrapped = native && ctx.accounts.base_token_account.is_native();
+  let base_destination = if wrapped {
+    wrap_sol(
+      &ctx.accounts.system_program,
+      &ctx.accounts.base_token_program,
+      &ctx.accounts.rent,
+      &ctx.accounts.authority,
+      &ctx.accounts.base_mint.to_account_info(),
+      &ctx.accounts.wsol_account,
+      0,
+    )?;
+    ctx.accounts.wsol_account.to_account_info()
+  } else {
+    ctx.accounts.base_token_account.to_account_info()
+  };
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.base_token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.base_treasury.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
+        to: base_destination,
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    base_amount_after_fee,
+    ctx.accounts.base_mint.decimals,
+  )?;
+  if wrapped {
+    unwrap_sol(
+      &ctx.accounts.base_token_program,
+      &ctx.accounts.authority,
+      &ctx.accounts.wsol_account,
+    )?;
+  }
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.base_treasury,
+    base_surplus,
+  )?;
+  Ok(())
+}
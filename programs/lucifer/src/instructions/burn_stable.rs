@@ -1,106 +1,313 @@
+use crate::errors::ErrorCode;
 use crate::oracle::*;
 use crate::schema::pool::*;
+use crate::utils::*;
 
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 
 #[derive(Accounts)]
 pub struct BurnStable<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
   // Pool's info
-  #[account(mut, has_one=base_mint)]
+  #[account(mut, has_one = base_mint, has_one = stable_mint, has_one = lpt_mint)]
   pub pool: Account<'info, Pool>,
   #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
   /// CHECK: Just a pure account
   pub treasurer: AccountInfo<'info>,
   // Pool's Mints
-  pub base_mint: Box<Account<'info, token::Mint>>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
   #[account(
     mut,
     seeds = [b"stable_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub stable_mint: Account<'info, token::Mint>,
+  pub stable_mint: InterfaceAccount<'info, token_interface::Mint>,
   #[account(
     mut,
     seeds = [b"lpt_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub lpt_mint: Account<'info, token::Mint>,
+  pub lpt_mint: InterfaceAccount<'info, token_interface::Mint>,
   // Pool's token account
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
   )]
-  pub base_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = stable_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Wallet's Token Accounts
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
   )]
-  pub base_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = stable_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Temporary wrapped SOL account, a fresh keypair that only signs with native
+  #[account(mut)]
+  /// CHECK: Created and closed within the instruction when native
+  pub wsol_account: AccountInfo<'info>,
 
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn exec(ctx: Context<BurnStable>, stable_amount: u64) -> Result<()> {
+pub fn exec(ctx: Context<BurnStable>, stable_amount: u64, native: bool) -> Result<()> {
+  let stable_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.stable_treasury)?;
+  let base_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.base_treasury)?;
   let pool = &mut ctx.accounts.pool;
   // Burn stable mint
   let burn_stable = CpiContext::new(
-    ctx.accounts.token_program.to_account_info(),
-    token::Burn {
+    ctx.accounts.pool_token_program.to_account_info(),
+    token_interface::Burn {
       from: ctx.accounts.stable_token_account.to_account_info(),
       mint: ctx.accounts.stable_mint.to_account_info(),
       authority: ctx.accounts.authority.to_account_info(),
     },
   );
-  token::burn(burn_stable, stable_amount)?;
+  token_interface::burn(burn_stable, stable_amount)?;
   msg!("burn stable {}", stable_amount);
   // Transfer base mint
   let seeds: &[&[&[u8]]] = &[&[
     "treasurer".as_ref(),
     &pool.key().to_bytes(),
-    &[*ctx.bumps.get("treasurer").unwrap()],
+    &[ctx.bumps.treasurer],
   ]];
-  let stable_amount_after_fee = pool.calc_fee(stable_amount).unwrap();
-  token::transfer(
+  let fee_amount = pool
+    .calc_fee(stable_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let stable_amount_after_fee = stable_amount
+    .checked_sub(fee_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let base_amount = pool
+    .calc_base_amount(stable_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let base_amount_after_fee = pool
+    .calc_base_amount(stable_amount_after_fee)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let base_fee_amount = base_amount
+    .checked_sub(base_amount_after_fee)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Pay out as lamports with native
+  let wrapped = native && ctx.accounts.base_token_account.is_native();
+  let base_destination = if wrapped {
+    wrap_sol(
+      &ctx.accounts.system_program,
+      &ctx.accounts.base_token_program,
+      &ctx.accounts.rent,
+      &ctx.accounts.authority,
+      &ctx.accounts.base_mint.to_account_info(),
+      &ctx.accounts.wsol_account,
+      0,
+    )?;
+    ctx.accounts.wsol_account.to_account_info()
+  } else {
+    ctx.accounts.base_token_account.to_account_info()
+  };
+  token_interface::transfer_checked(
     CpiContext::new_with_signer(
-      ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
+      ctx.accounts.base_token_program.to_account_info(),
+      token_interface::TransferChecked {
         from: ctx.accounts.base_treasury.to_account_info(),
-        to: ctx.accounts.base_token_account.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
+        to: base_destination,
         authority: ctx.accounts.treasurer.to_account_info(),
       },
       seeds,
     ),
-    stable_amount_after_fee,
+    base_amount_after_fee,
+    ctx.accounts.base_mint.decimals,
   )?;
-  pool.base_balance -= stable_amount_after_fee;
+  if wrapped {
+    unwrap_sol(
+      &ctx.accounts.base_token_program,
+      &ctx.accounts.authority,
+      &ctx.accounts.wsol_account,
+    )?;
+  }
+  // The whole burned amount leaves the backing, the fee moves to the lending reserve
+  pool.base_balance = pool
+    .base_balance
+    .checked_sub(base_amount)
+    .ok_or(ErrorCode::InsufficientLiquidity)?;
+  pool.lending_reserve = pool
+    .lending_reserve
+    .checked_add(base_fee_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
   // Update Fee
-  let amounts = vec![0, stable_amount - stable_amount_after_fee];
+  let amounts = vec![0, fee_amount];
   let reserves = vec![pool.balance, pool.stable_balance];
   let supply = ctx.accounts.lpt_mint.supply;
-  pool.total_lpt_fee +=
-    calc_lpt_receive_add_full_side(supply, &amounts, &reserves, pool.fee).unwrap();
+  let lpt_fee = calc_lpt_receive_add_full_side(supply, &amounts, &reserves, pool.fee)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.total_lpt_fee = pool
+    .total_lpt_fee
+    .checked_add(lpt_fee)
+    .ok_or(ErrorCode::MathOverflow)?;
 
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.stable_treasury,
+    stable_surplus,
+  )?;
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.base_treasury,
+    base_surplus,
+  )?;
+  check_stable_backing(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.stable_mint,
+    &mut ctx.accounts.base_treasury,
+  )?;
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+  use anchor_lang::error::ErrorCode as AnchorErrorCode;
+  use anchor_lang::solana_program::program_pack::Pack;
+  use anchor_spl::token::spl_token;
+
+  fn burn_stable_accounts(
+    fixture: &PoolFixture,
+    authority: Pubkey,
+    base_mint: Pubkey,
+    stable_treasury: AccountInfo<'static>,
+  ) -> Vec<AccountInfo<'static>> {
+    let pool = &fixture.pool;
+    [
+      fixture.pool_accounts(authority),
+      vec![
+        fixture.mint_info(base_mint),
+        fixture.mint_info(pool.stable_mint),
+        fixture.mint_info(pool.lpt_mint),
+        fixture.treasury_info(base_mint),
+        stable_treasury,
+        associated_token_info(base_mint, authority, 0),
+        associated_token_info(pool.stable_mint, authority, 0),
+        account_info(Pubkey::new_unique(), System::id(), vec![]),
+      ],
+      program_infos(2),
+    ]
+    .concat()
+  }
+
+  #[test]
+  fn accepts_pool_accounts() {
+    let fixture = PoolFixture::new();
+    let treasury = fixture.treasury_info(fixture.pool.stable_mint);
+    let accounts = burn_stable_accounts(
+      &fixture,
+      Pubkey::new_unique(),
+      fixture.pool.base_mint,
+      treasury,
+    );
+    assert_eq!(constraint_error::<BurnStable>(&accounts), None);
+  }
+
+  #[test]
+  fn rejects_foreign_base_mint() {
+    let fixture = PoolFixture::new();
+    let treasury = fixture.treasury_info(fixture.pool.stable_mint);
+    let accounts = burn_stable_accounts(
+      &fixture,
+      Pubkey::new_unique(),
+      Pubkey::new_unique(),
+      treasury,
+    );
+    assert_eq!(
+      constraint_error::<BurnStable>(&accounts),
+      Some(AnchorErrorCode::ConstraintHasOne.into())
+    );
+  }
+
+  #[test]
+  fn rejects_foreign_treasuries() {
+    let fixture = PoolFixture::new();
+    let other = PoolFixture::new();
+    // The stable treasury of another pool
+    let treasury = other.treasury_info(other.pool.stable_mint);
+    let accounts = burn_stable_accounts(
+      &fixture,
+      Pubkey::new_unique(),
+      fixture.pool.base_mint,
+      treasury,
+    );
+    assert!(constraint_error::<BurnStable>(&accounts).is_some());
+    // A stable account the treasurer doesn't own
+    let treasury = associated_token_info(fixture.pool.stable_mint, Pubkey::new_unique(), 0);
+    let accounts = burn_stable_accounts(
+      &fixture,
+      Pubkey::new_unique(),
+      fixture.pool.base_mint,
+      treasury,
+    );
+    assert_eq!(
+      constraint_error::<BurnStable>(&accounts),
+      Some(AnchorErrorCode::ConstraintTokenOwner.into())
+    );
+  }
+
+  #[test]
+  fn vault_minters_cannot_redeem_against_base_backing() {
+    let mut fixture = PoolFixture::new();
+    fixture.pool.base_balance = 1_000;
+    let authority = Pubkey::new_unique();
+    let vault_stable_mint = find_pda(&[b"vault_stable_mint", &fixture.key.to_bytes()]);
+    let pool = &fixture.pool;
+    // Vault stable can't stand in for the pool stable
+    let mut accounts = burn_stable_accounts(
+      &fixture,
+      authority,
+      pool.base_mint,
+      fixture.treasury_info(pool.stable_mint),
+    );
+    accounts[9] = associated_token_info(vault_stable_mint, authority, 1_000);
+    assert_eq!(
+      constraint_error::<BurnStable>(&accounts),
+      Some(AnchorErrorCode::ConstraintAssociated.into())
+    );
+    // And the minter holds none of the stable backed by base
+    let mut accounts = burn_stable_accounts(
+      &fixture,
+      authority,
+      pool.base_mint,
+      fixture.treasury_info(pool.stable_mint),
+    );
+    accounts[4] = mint_info(pool.stable_mint, pool.treasurer, 1_000);
+    accounts[6] = associated_token_info(pool.base_mint, pool.treasurer, 1_000);
+    let result = exec_instruction::<BurnStable>(&accounts, |ctx| exec(ctx, 1_000, false));
+    assert!(result.is_err());
+    assert_eq!(
+      spl_token::state::Account::unpack(&accounts[6].data.borrow())
+        .unwrap()
+        .amount,
+      1_000
+    );
+  }
+}
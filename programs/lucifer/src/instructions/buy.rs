@@ -1,100 +1,136 @@
+use crate::errors::ErrorCode;
 use crate::instructions::*;
-use crate::oracle::*;
 use crate::schema::*;
+use crate::utils::*;
 
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 
 #[derive(Accounts)]
 pub struct Buy<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
   // Pool's info
-  #[account(mut, has_one=mint)]
+  #[account(
+    mut,
+    has_one = mint,
+    has_one = base_mint,
+    has_one = stable_mint,
+    has_one = lpt_mint
+  )]
   pub pool: Account<'info, Pool>,
   #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
   /// CHECK: Just a pure account
   pub treasurer: AccountInfo<'info>,
   // Pool's Mints
-  pub mint: Box<Account<'info, token::Mint>>,
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
   #[account(
     mut,
     seeds = [b"stable_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub stable_mint: Account<'info, token::Mint>,
-  pub base_mint: Box<Account<'info, token::Mint>>,
+  pub stable_mint: InterfaceAccount<'info, token_interface::Mint>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
   #[account(
     mut,
     seeds = [b"lpt_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub lpt_mint: Account<'info, token::Mint>,
+  pub lpt_mint: InterfaceAccount<'info, token_interface::Mint>,
   // Pool's token account
   #[account(
     mut,
     associated_token::mint = mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
   )]
-  pub treasury: Box<Account<'info, token::TokenAccount>>,
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = stable_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
   )]
-  pub base_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = lpt_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Wallet's Token Accounts
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
   )]
-  pub token_account: Box<Account<'info, token::TokenAccount>>,
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = stable_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
   )]
-  pub base_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = lpt_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Temporary wrapped SOL account, a fresh keypair that only signs with native
+  #[account(mut)]
+  /// CHECK: Created and closed within the instruction when native
+  pub wsol_account: AccountInfo<'info>,
 
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn exec(ctx: Context<Buy>, stable_amount: u64, base_amount: u64) -> Result<()> {
+pub fn exec<'info>(
+  ctx: Context<'_, '_, 'info, 'info, Buy<'info>>,
+  stable_amount: u64,
+  base_amount: u64,
+  native: bool,
+) -> Result<()> {
+  let surpluses = calc_surpluses(
+    &ctx.accounts.pool,
+    &ctx.accounts.treasury,
+    &ctx.accounts.stable_treasury,
+    &ctx.accounts.base_treasury,
+  )?;
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  check_active(&ctx.accounts.pool)?;
   let mut total_stable_amount = stable_amount;
   // Call inner instructions Mint Stable
   if base_amount > 0 {
+    let base_balance = ctx.accounts.pool.base_balance;
     let accounts = &mut MintStable {
       authority: ctx.accounts.authority.clone(),
       pool: ctx.accounts.pool.clone(),
@@ -105,69 +141,241 @@ pub fn exec(ctx: Context<Buy>, stable_amount: u64, base_amount: u64) -> Result<(
       stable_treasury: ctx.accounts.stable_treasury.clone(),
       base_token_account: ctx.accounts.base_token_account.clone(),
       stable_token_account: ctx.accounts.stable_token_account.clone(),
+      wsol_account: ctx.accounts.wsol_account.clone(),
       system_program: ctx.accounts.system_program.clone(),
-      token_program: ctx.accounts.token_program.clone(),
+      base_token_program: ctx.accounts.base_token_program.clone(),
+      pool_token_program: ctx.accounts.pool_token_program.clone(),
       associated_token_program: ctx.accounts.associated_token_program.clone(),
       rent: ctx.accounts.rent.clone(),
     };
-    let mint_to_context = Context::new(&ctx.program_id, accounts, &[], ctx.bumps.clone());
-    mint_stable::exec(mint_to_context, base_amount).unwrap();
+    let bumps = MintStableBumps {
+      treasurer: ctx.bumps.treasurer,
+      stable_mint: ctx.bumps.stable_mint,
+    };
+    let mint_to_context = Context::new(&ctx.program_id, accounts, &[], bumps);
+    mint_stable::exec(mint_to_context, base_amount, native)?;
+    // The nested handler wrote its own copy of the pool, persist it before reloading
+    accounts.pool.exit(ctx.program_id)?;
     ctx.accounts.pool.reload()?;
-    total_stable_amount += base_amount;
+    // Stable minted for the base that reached the treasury
+    let base_received = ctx
+      .accounts
+      .pool
+      .base_balance
+      .checked_sub(base_balance)
+      .ok_or(ErrorCode::MathOverflow)?;
+    let stable_amount = ctx
+      .accounts
+      .pool
+      .calc_stable_amount(base_received)
+      .ok_or(ErrorCode::MathOverflow)?;
+    total_stable_amount = total_stable_amount
+      .checked_add(stable_amount)
+      .ok_or(ErrorCode::MathOverflow)?;
   }
-  //
-  let pool = &mut ctx.accounts.pool;
-  // Transfer Mint
-  let amount = calc_ask_amount_swap(
-    total_stable_amount,
-    pool.stable_balance,
-    pool.balance,
-    pool.fee,
-  )
-  .unwrap();
-  let amount_ignore_fee =
-    calc_ask_amount_swap(total_stable_amount, pool.stable_balance, pool.balance, 0).unwrap();
   // Transfer Stable Mint
-  msg!("11");
   if total_stable_amount > 0 {
-    token::transfer(
-      CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
-          from: ctx.accounts.stable_token_account.to_account_info(),
-          to: ctx.accounts.stable_treasury.to_account_info(),
-          authority: ctx.accounts.authority.to_account_info(),
-        },
-      ),
+    total_stable_amount = transfer_in(
+      &ctx.accounts.pool_token_program,
+      ctx.accounts.stable_token_account.to_account_info(),
+      &mut ctx.accounts.stable_treasury,
+      &ctx.accounts.stable_mint,
+      ctx.accounts.authority.to_account_info(),
+      &[],
       total_stable_amount,
     )?;
   }
-  pool.stable_balance += total_stable_amount;
-  pool.balance -= amount;
+  let pool = &mut ctx.accounts.pool;
+  check_trading_halt(pool, current_time)?;
+  let pre_price = pool.calc_spot_price().ok_or(ErrorCode::MathOverflow)?;
+  let pre_invariant = pool
+    .calc_weighted_invariant()
+    .ok_or(ErrorCode::MathOverflow)?;
+  let fee = calc_discounted_fee(
+    ctx.program_id,
+    &pool.key(),
+    &ctx.accounts.authority.key(),
+    ctx.remaining_accounts,
+    pool
+      .calc_swap_fee(current_time)
+      .ok_or(ErrorCode::MathOverflow)?,
+  )?;
+  // Swap stable for mint
+  let supply = ctx.accounts.lpt_mint.supply;
+  let (amount, fee_amount) = pool
+    .swap(total_stable_amount, true, fee, supply, current_time)
+    .ok_or(ErrorCode::InsufficientLiquidity)?;
+  credit_referral_swap(
+    ctx.program_id,
+    pool,
+    &ctx.accounts.authority.key(),
+    ctx.remaining_accounts,
+    fee_amount,
+    false,
+    total_stable_amount,
+  )?;
   // Transfer Mint
   let seeds: &[&[&[u8]]] = &[&[
     "treasurer".as_ref(),
     &pool.key().to_bytes(),
-    &[*ctx.bumps.get("treasurer").unwrap()],
+    &[ctx.bumps.treasurer],
   ]];
-  msg!("22");
-  token::transfer(
+  // Pay out as lamports with native
+  let wrapped = native && ctx.accounts.token_account.is_native();
+  let destination = if wrapped {
+    wrap_sol(
+      &ctx.accounts.system_program,
+      &ctx.accounts.token_program,
+      &ctx.accounts.rent,
+      &ctx.accounts.authority,
+      &ctx.accounts.mint.to_account_info(),
+      &ctx.accounts.wsol_account,
+      0,
+    )?;
+    ctx.accounts.wsol_account.to_account_info()
+  } else {
+    ctx.accounts.token_account.to_account_info()
+  };
+  token_interface::transfer_checked(
     CpiContext::new_with_signer(
       ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
+      token_interface::TransferChecked {
         from: ctx.accounts.treasury.to_account_info(),
-        to: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: destination,
         authority: ctx.accounts.treasurer.to_account_info(),
       },
       seeds,
     ),
     amount,
+    ctx.accounts.mint.decimals,
+  )?;
+  if wrapped {
+    unwrap_sol(
+      &ctx.accounts.token_program,
+      &ctx.accounts.authority,
+      &ctx.accounts.wsol_account,
+    )?;
+  }
+  check_swap_invariant(pool, pre_invariant)?;
+  check_price_impact(pool, pre_price, current_time)?;
+  pool
+    .update_volatility(current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Oracle guard band on the post-trade price
+  check_oracle_band(
+    pool,
+    ctx.remaining_accounts,
+    ctx.accounts.mint.decimals,
+    ctx.accounts.stable_mint.decimals,
+    current_time,
+  )?;
+  check_invariants(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.treasury,
+    &mut ctx.accounts.stable_treasury,
+    &mut ctx.accounts.base_treasury,
+    &mut ctx.accounts.stable_mint,
+    surpluses,
   )?;
-  // Update Fee
-  let amounts = vec![amount_ignore_fee - amount, 0];
-  let reserves = vec![pool.balance, pool.stable_balance];
-  let supply = ctx.accounts.lpt_mint.supply;
-  pool.total_lpt_fee +=
-    calc_lpt_receive_add_full_side(supply, &amounts, &reserves, pool.fee).unwrap();
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+  use anchor_lang::solana_program::program_pack::Pack;
+  use anchor_spl::token::spl_token;
+  use anchor_spl::token_2022;
+
+  const RESERVE: u64 = 1_000_000;
+
+  fn buy_accounts(
+    fixture: &PoolFixture,
+    authority: Pubkey,
+    base_amount: u64,
+    donation: u64,
+  ) -> Vec<AccountInfo<'static>> {
+    let pool = &fixture.pool;
+    [
+      fixture.pool_accounts(authority),
+      vec![
+        fixture.mint_info(pool.mint),
+        mint_info(pool.stable_mint, pool.treasurer, RESERVE),
+        fixture.mint_info(pool.base_mint),
+        mint_info(pool.lpt_mint, pool.treasurer, RESERVE),
+        associated_token_info(pool.mint, pool.treasurer, RESERVE),
+        associated_token_info(pool.stable_mint, pool.treasurer, RESERVE),
+        associated_token_info(pool.base_mint, pool.treasurer, RESERVE + donation),
+        fixture.treasury_info(pool.lpt_mint),
+        associated_token_info(pool.mint, authority, 0),
+        associated_token_info(pool.stable_mint, authority, 0),
+        associated_token_info(pool.base_mint, authority, base_amount),
+        associated_token_info(pool.lpt_mint, authority, 0),
+        account_info(Pubkey::new_unique(), System::id(), vec![]),
+      ],
+      program_infos(3),
+    ]
+    .concat()
+  }
+
+  fn reserve_fixture() -> PoolFixture {
+    let mut fixture = PoolFixture::new();
+    fixture.pool.balance = RESERVE;
+    fixture.pool.stable_balance = RESERVE;
+    fixture.pool.base_balance = RESERVE;
+    fixture
+  }
+
+  fn buy_with_base(base_amount: u64, donation: u64) -> (Pool, Vec<AccountInfo<'static>>) {
+    let fixture = reserve_fixture();
+    let accounts = buy_accounts(&fixture, Pubkey::new_unique(), base_amount, donation);
+    exec_instruction::<Buy>(&accounts, |ctx| exec(ctx, 0, base_amount, false)).unwrap();
+    let pool = Pool::try_deserialize(&mut &accounts[1].data.borrow()[..]).unwrap();
+    (pool, accounts)
+  }
+
+  fn token_amount(account: &AccountInfo) -> u64 {
+    spl_token::state::Account::unpack(&account.data.borrow())
+      .unwrap()
+      .amount
+  }
+
+  #[test]
+  fn books_the_base_paid_in() {
+    let (pool, accounts) = buy_with_base(1_000, 0);
+    // Minted stable is paid straight into the pool
+    assert_eq!(pool.base_balance, RESERVE + 1_000);
+    assert_eq!(pool.stable_balance, RESERVE + 1_000);
+    assert_eq!(token_amount(&accounts[9]), RESERVE + 1_000);
+    assert_eq!(token_amount(&accounts[8]), RESERVE + 1_000);
+    assert_eq!(RESERVE - pool.balance, token_amount(&accounts[11]));
+    assert!(pool.balance < RESERVE);
+  }
+
+  #[test]
+  fn keeps_donations_out_of_the_ledger() {
+    let (pool, accounts) = buy_with_base(1_000, 5);
+    assert_eq!(pool.base_balance, RESERVE + 1_000);
+    assert_eq!(token_amount(&accounts[9]), RESERVE + 1_005);
+  }
+
+  #[test]
+  fn books_the_base_received_after_the_transfer_fee() {
+    let fixture = reserve_fixture();
+    let pool = &fixture.pool;
+    let authority = Pubkey::new_unique();
+    let mut accounts = buy_accounts(&fixture, authority, 1_000, 0);
+    // A Token-2022 base mint withholding 1% of every transfer
+    accounts[5] = fee_mint_info(pool.base_mint, pool.treasurer, 0, 100);
+    accounts[9] = fee_associated_token_info(pool.base_mint, pool.treasurer, RESERVE);
+    accounts[13] = fee_associated_token_info(pool.base_mint, authority, 1_000);
+    accounts[18] = program_info(token_2022::ID);
+    exec_instruction::<Buy>(&accounts, |ctx| exec(ctx, 0, 1_000, false)).unwrap();
+    let pool = Pool::try_deserialize(&mut &accounts[1].data.borrow()[..]).unwrap();
+    // Only the base that arrived backs stable
+    assert_eq!(pool.base_balance, RESERVE + 990);
+    assert_eq!(pool.stable_balance, RESERVE + 990);
+  }
+}
@@ -0,0 +1,83 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct CancelDcaOrder<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Order's info
+  #[account(
+    mut,
+    close = authority,
+    has_one = authority,
+    has_one = pool,
+    has_one = bid_mint,
+    has_one = escrow
+  )]
+  pub dca_order: Account<'info, DcaOrder>,
+  pub bid_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mut)]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = bid_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub bid_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<CancelDcaOrder>) -> Result<()> {
+  // Refund escrowed bid token
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &ctx.accounts.pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.escrow.to_account_info(),
+        mint: ctx.accounts.bid_mint.to_account_info(),
+        to: ctx.accounts.bid_token_account.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    ctx.accounts.escrow.amount,
+    ctx.accounts.bid_mint.decimals,
+  )?;
+  token_interface::close_account(CpiContext::new_with_signer(
+    ctx.accounts.token_program.to_account_info(),
+    token_interface::CloseAccount {
+      account: ctx.accounts.escrow.to_account_info(),
+      destination: ctx.accounts.authority.to_account_info(),
+      authority: ctx.accounts.treasurer.to_account_info(),
+    },
+    seeds,
+  ))?;
+  let pool = &mut ctx.accounts.pool;
+  pool.open_orders = pool
+    .open_orders
+    .checked_sub(1)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct ClaimLpLock<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Farm's info
+  #[account(
+    mut,
+    has_one = pool,
+    has_one = reward_mint,
+    has_one = reward_vault
+  )]
+  pub farm: Account<'info, Farm>,
+  pub reward_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mut)]
+  pub reward_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Lock's info
+  #[account(mut, has_one = authority, has_one = farm)]
+  pub lp_lock: Account<'info, LpLock>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = reward_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = reward_token_program
+  )]
+  pub reward_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub reward_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<ClaimLpLock>) -> Result<()> {
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let farm = &mut ctx.accounts.farm;
+  let lp_lock = &mut ctx.accounts.lp_lock;
+  farm.update(current_time).ok_or(ErrorCode::MathOverflow)?;
+  let reward_amount = farm
+    .calc_pending_reward(lp_lock.weight, lp_lock.reward_debt)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if !(reward_amount > 0) {
+    return Ok(());
+  }
+  // Transfer reward
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &farm.pool.to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.reward_token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.reward_vault.to_account_info(),
+        mint: ctx.accounts.reward_mint.to_account_info(),
+        to: ctx.accounts.reward_token_account.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    reward_amount,
+    ctx.accounts.reward_mint.decimals,
+  )?;
+  lp_lock.reward_debt = farm
+    .calc_reward_debt(lp_lock.weight)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(())
+}
@@ -0,0 +1,166 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct ClaimReferral<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut, has_one = mint, has_one = stable_mint, has_one = lpt_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mut)]
+  pub lpt_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
+  )]
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = stable_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Referral's info
+  #[account(
+    mut,
+    has_one = pool,
+    constraint = referral.referrer == authority.key() @ ErrorCode::InvalidReferral,
+    seeds = [b"referral".as_ref(), &pool.key().to_bytes(), &authority.key().to_bytes()],
+    bump
+  )]
+  pub referral: Account<'info, Referral>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = stable_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = lpt_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
+  )]
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<ClaimReferral>) -> Result<()> {
+  let surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.treasury)?;
+  let stable_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.stable_treasury)?;
+  let pool = &mut ctx.accounts.pool;
+  let referral = &mut ctx.accounts.referral;
+  // Pool signature
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  // Fee shares from buys
+  if referral.reward > 0 {
+    token_interface::transfer_checked(
+      CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token_interface::TransferChecked {
+          from: ctx.accounts.treasury.to_account_info(),
+          mint: ctx.accounts.mint.to_account_info(),
+          to: ctx.accounts.token_account.to_account_info(),
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      referral.reward,
+      ctx.accounts.mint.decimals,
+    )?;
+    pool.referral_reserve = pool
+      .referral_reserve
+      .checked_sub(referral.reward)
+      .ok_or(ErrorCode::MathOverflow)?;
+  }
+  // Fee shares from sells
+  if referral.stable_reward > 0 {
+    token_interface::transfer_checked(
+      CpiContext::new_with_signer(
+        ctx.accounts.pool_token_program.to_account_info(),
+        token_interface::TransferChecked {
+          from: ctx.accounts.stable_treasury.to_account_info(),
+          mint: ctx.accounts.stable_mint.to_account_info(),
+          to: ctx.accounts.stable_token_account.to_account_info(),
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      referral.stable_reward,
+      ctx.accounts.stable_mint.decimals,
+    )?;
+    pool.referral_stable_reserve = pool
+      .referral_stable_reserve
+      .checked_sub(referral.stable_reward)
+      .ok_or(ErrorCode::MathOverflow)?;
+  }
+  // Fee shares from deposits
+  if referral.lpt_reward > 0 {
+    token_interface::mint_to(
+      CpiContext::new_with_signer(
+        ctx.accounts.pool_token_program.to_account_info(),
+        token_interface::MintTo {
+          to: ctx.accounts.lpt_token_account.to_account_info(),
+          mint: ctx.accounts.lpt_mint.to_account_info(),
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      referral.lpt_reward,
+    )?;
+    pool.lpt_supply = pool
+      .lpt_supply
+      .checked_add(referral.lpt_reward)
+      .ok_or(ErrorCode::MathOverflow)?;
+  }
+  msg!(
+    "Referral claim {} {} {}",
+    referral.reward,
+    referral.stable_reward,
+    referral.lpt_reward
+  );
+  referral.reward = 0;
+  referral.stable_reward = 0;
+  referral.lpt_reward = 0;
+  check_ledger(pool, &mut ctx.accounts.treasury, surplus)?;
+  check_ledger(pool, &mut ctx.accounts.stable_treasury, stable_surplus)?;
+  Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Vault's info
+  #[account(
+    mut,
+    close = authority,
+    has_one = authority,
+    has_one = pool,
+    has_one = escrow
+  )]
+  pub vault: Account<'info, Vault>,
+  #[account(mut)]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+}
+
+// Close a repaid and emptied vault, returning the rent
+pub fn exec(ctx: Context<CloseVault>) -> Result<()> {
+  let vault = &ctx.accounts.vault;
+  if vault.debt > 0 || vault.collateral > 0 || ctx.accounts.escrow.amount > 0 {
+    return err!(ErrorCode::VaultNotEmpty);
+  }
+  // Pool signature
+  let pool = &mut ctx.accounts.pool;
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  token_interface::close_account(CpiContext::new_with_signer(
+    ctx.accounts.token_program.to_account_info(),
+    token_interface::CloseAccount {
+      account: ctx.accounts.escrow.to_account_info(),
+      destination: ctx.accounts.authority.to_account_info(),
+      authority: ctx.accounts.treasurer.to_account_info(),
+    },
+    seeds,
+  ))?;
+  pool.open_vaults = pool
+    .open_vaults
+    .checked_sub(1)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(())
+}
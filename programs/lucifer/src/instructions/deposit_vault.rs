@@ -0,0 +1,242 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct DepositVault<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut, has_one = base_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    mut,
+    seeds = [b"vault_stable_mint".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub vault_stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Vault's info
+  #[account(
+    has_one = pool,
+    has_one = oracle,
+    seeds = [b"vault_config".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub vault_config: Account<'info, VaultConfig>,
+  /// CHECK: Parsed as a Pyth-compatible price account
+  pub oracle: AccountInfo<'info>,
+  #[account(mut, has_one = authority, has_one = pool, has_one = escrow)]
+  pub vault: Account<'info, Vault>,
+  #[account(mut)]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = base_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = vault_stable_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
+  )]
+  pub vault_stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Temporary wrapped SOL account, a fresh keypair that only signs with native
+  #[account(mut)]
+  /// CHECK: Created and closed within the instruction when native
+  pub wsol_account: AccountInfo<'info>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+// Add collateral and mint stable against it, up to the minimum collateral ratio
+pub fn exec(
+  ctx: Context<DepositVault>,
+  collateral_amount: u64,
+  stable_amount: u64,
+  native: bool,
+) -> Result<()> {
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  check_active(&ctx.accounts.pool)?;
+  // Transfer collateral, only what the escrow received is counted
+  let mut collateral_amount = collateral_amount;
+  if collateral_amount > 0 {
+    // Pay in from lamports with native
+    let wrapped = native && ctx.accounts.base_token_account.is_native();
+    let base_source = if wrapped {
+      wrap_sol(
+        &ctx.accounts.system_program,
+        &ctx.accounts.base_token_program,
+        &ctx.accounts.rent,
+        &ctx.accounts.authority,
+        &ctx.accounts.base_mint.to_account_info(),
+        &ctx.accounts.wsol_account,
+        collateral_amount,
+      )?;
+      ctx.accounts.wsol_account.to_account_info()
+    } else {
+      ctx.accounts.base_token_account.to_account_info()
+    };
+    collateral_amount = transfer_in(
+      &ctx.accounts.base_token_program,
+      base_source,
+      &mut ctx.accounts.escrow,
+      &ctx.accounts.base_mint,
+      ctx.accounts.authority.to_account_info(),
+      &[],
+      collateral_amount,
+    )?;
+    if wrapped {
+      unwrap_sol(
+        &ctx.accounts.base_token_program,
+        &ctx.accounts.authority,
+        &ctx.accounts.wsol_account,
+      )?;
+    }
+  }
+  let vault_config = &ctx.accounts.vault_config;
+  let pool = &mut ctx.accounts.pool;
+  let vault = &mut ctx.accounts.vault;
+  vault
+    .accrue(vault_config.stability_fee, current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  vault.collateral = vault
+    .collateral
+    .checked_add(collateral_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Mint stable
+  if stable_amount > 0 {
+    vault.principal = vault
+      .principal
+      .checked_add(stable_amount)
+      .ok_or(ErrorCode::MathOverflow)?;
+    vault.debt = vault
+      .debt
+      .checked_add(stable_amount)
+      .ok_or(ErrorCode::MathOverflow)?;
+    pool.vault_debt = pool
+      .vault_debt
+      .checked_add(stable_amount)
+      .ok_or(ErrorCode::MathOverflow)?;
+    let collateral_value = calc_vault_collateral_value(
+      vault_config,
+      pool,
+      &ctx.accounts.oracle,
+      vault.collateral,
+      current_time,
+    )?;
+    let collateral_ratio = vault
+      .calc_collateral_ratio(collateral_value)
+      .ok_or(ErrorCode::MathOverflow)?;
+    if collateral_ratio < vault_config.min_collateral_ratio {
+      return err!(ErrorCode::Undercollateralized);
+    }
+    let seeds: &[&[&[u8]]] = &[&[
+      "treasurer".as_ref(),
+      &pool.key().to_bytes(),
+      &[ctx.bumps.treasurer],
+    ]];
+    token_interface::mint_to(
+      CpiContext::new_with_signer(
+        ctx.accounts.pool_token_program.to_account_info(),
+        token_interface::MintTo {
+          to: ctx.accounts.vault_stable_token_account.to_account_info(),
+          mint: ctx.accounts.vault_stable_mint.to_account_info(),
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      stable_amount,
+    )?;
+    msg!("mint vault stable {}", stable_amount);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+  use anchor_lang::solana_program::program_pack::Pack;
+  use anchor_spl::token::spl_token;
+
+  #[test]
+  fn mints_vault_stable_apart_from_the_pool_stable() {
+    let fixture = PoolFixture::new();
+    let pool = &fixture.pool;
+    let authority = Pubkey::new_unique();
+    let oracle = Pubkey::new_unique();
+    let escrow = Pubkey::new_unique();
+    let vault_stable_mint = find_pda(&[b"vault_stable_mint", &fixture.key.to_bytes()]);
+    let vault_config = VaultConfig {
+      pool: fixture.key,
+      oracle,
+      oracle_max_age: 60,
+      min_collateral_ratio: 1_500_000_000,
+      liquidation_ratio: 1_200_000_000,
+      liquidation_bonus: 100_000_000,
+      stability_fee: 0,
+    };
+    let vault = Vault {
+      authority,
+      pool: fixture.key,
+      escrow,
+      collateral: 0,
+      principal: 0,
+      debt: 0,
+      last_accrued_at: CURRENT_TIME,
+    };
+    // The base is worth 2 stable
+    let price_feed = PriceFeed {
+      price: 200,
+      conf: 0,
+      expo: -2,
+      status: PRICE_FEED_STATUS_TRADING,
+      timestamp: CURRENT_TIME,
+    };
+    let accounts = [
+      fixture.pool_accounts(authority),
+      vec![
+        fixture.mint_info(pool.base_mint),
+        fixture.mint_info(vault_stable_mint),
+        program_account_info(
+          find_pda(&[b"vault_config", &fixture.key.to_bytes()]),
+          &vault_config,
+        ),
+        price_feed_info(oracle, &price_feed),
+        program_account_info(Pubkey::new_unique(), &vault),
+        token_account_info(escrow, pool.base_mint, pool.treasurer, 0),
+        associated_token_info(pool.base_mint, authority, 1_000),
+        associated_token_info(vault_stable_mint, authority, 0),
+        account_info(Pubkey::new_unique(), System::id(), vec![]),
+      ],
+      program_infos(2),
+    ]
+    .concat();
+    exec_instruction::<DepositVault>(&accounts, |ctx| exec(ctx, 1_000, 1_000, false)).unwrap();
+    let pool = Pool::try_deserialize(&mut &accounts[1].data.borrow()[..]).unwrap();
+    assert_eq!(pool.vault_debt, 1_000);
+    let vault_stable = spl_token::state::Mint::unpack(&accounts[4].data.borrow()).unwrap();
+    assert_eq!(vault_stable.supply, 1_000);
+    let vault_stable_account =
+      spl_token::state::Account::unpack(&accounts[10].data.borrow()).unwrap();
+    assert_eq!(vault_stable_account.amount, 1_000);
+  }
+}
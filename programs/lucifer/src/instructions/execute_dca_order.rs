@@ -0,0 +1,243 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct ExecuteDcaOrder<'info> {
+  #[account(mut)]
+  pub keeper: Signer<'info>,
+  #[account(mut)]
+  /// CHECK: Order's owner, only receives tokens and rent
+  pub authority: AccountInfo<'info>,
+  // Pool's info
+  #[account(mut, has_one = mint, has_one = base_mint, has_one = lpt_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    mut,
+    seeds = [b"stable_mint".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub lpt_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Order's info
+  #[account(mut, has_one = authority, has_one = pool, has_one = escrow)]
+  pub dca_order: Account<'info, DcaOrder>,
+  #[account(mut)]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
+  )]
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = stable_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = base_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = keeper,
+    associated_token::mint = mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<ExecuteDcaOrder>) -> Result<()> {
+  let surpluses = calc_surpluses(
+    &ctx.accounts.pool,
+    &ctx.accounts.treasury,
+    &ctx.accounts.stable_treasury,
+    &ctx.accounts.base_treasury,
+  )?;
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  check_active(&ctx.accounts.pool)?;
+  let dca_order = &mut ctx.accounts.dca_order;
+  if current_time < dca_order.next_execution_time {
+    return err!(ErrorCode::DcaNotDue);
+  }
+  let slice_amount = dca_order
+    .amount_per_interval
+    .min(ctx.accounts.escrow.amount);
+  if !(slice_amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  let pool = &mut ctx.accounts.pool;
+  check_trading_halt(pool, current_time)?;
+  // Pool signature
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  let bid_base = dca_order.bid_mint == pool.base_mint;
+  let stable_amount = if bid_base {
+    // Mint stable against the base slice the treasury received, straight into the pool
+    let base_received = transfer_in(
+      &ctx.accounts.base_token_program,
+      ctx.accounts.escrow.to_account_info(),
+      &mut ctx.accounts.base_treasury,
+      &ctx.accounts.base_mint,
+      ctx.accounts.treasurer.to_account_info(),
+      seeds,
+      slice_amount,
+    )?;
+    let stable_amount = pool
+      .calc_stable_amount(base_received)
+      .ok_or(ErrorCode::MathOverflow)?;
+    token_interface::mint_to(
+      CpiContext::new_with_signer(
+        ctx.accounts.pool_token_program.to_account_info(),
+        token_interface::MintTo {
+          to: ctx.accounts.stable_treasury.to_account_info(),
+          mint: ctx.accounts.stable_mint.to_account_info(),
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      stable_amount,
+    )?;
+    pool.base_balance = pool
+      .base_balance
+      .checked_add(base_received)
+      .ok_or(ErrorCode::MathOverflow)?;
+    stable_amount
+  } else {
+    transfer_in(
+      &ctx.accounts.pool_token_program,
+      ctx.accounts.escrow.to_account_info(),
+      &mut ctx.accounts.stable_treasury,
+      &ctx.accounts.stable_mint,
+      ctx.accounts.treasurer.to_account_info(),
+      seeds,
+      slice_amount,
+    )?
+  };
+  // Swap stable for mint, bounded by the max slippage from the oracle price, or from
+  // the price of the last execution. The spot price can be moved within the crank's
+  // transaction, so it can't set the floor.
+  let pre_price = pool.calc_spot_price().ok_or(ErrorCode::MathOverflow)?;
+  let pre_invariant = pool
+    .calc_weighted_invariant()
+    .ok_or(ErrorCode::MathOverflow)?;
+  let reference_price = match load_oracle_price(pool, ctx.remaining_accounts, current_time)? {
+    Some(oracle_price) => pool
+      .calc_oracle_spot_price(
+        oracle_price,
+        ctx.accounts.mint.decimals,
+        ctx.accounts.stable_mint.decimals,
+      )
+      .ok_or(ErrorCode::MathOverflow)?,
+    None => dca_order.reference_price,
+  };
+  let min_amount = dca_order
+    .calc_min_amount_out(stable_amount, reference_price)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let fee = pool
+    .calc_swap_fee(current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let supply = ctx.accounts.lpt_mint.supply;
+  let (amount, _) = pool
+    .swap(stable_amount, true, fee, supply, current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if amount < min_amount {
+    return err!(ErrorCode::Slippage);
+  }
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.treasury.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.token_account.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    amount,
+    ctx.accounts.mint.decimals,
+  )?;
+  msg!("DCA slice {} for {}", stable_amount, amount);
+  check_swap_invariant(pool, pre_invariant)?;
+  check_price_impact(pool, pre_price, current_time)?;
+  pool
+    .update_volatility(current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  check_oracle_band(
+    pool,
+    ctx.remaining_accounts,
+    ctx.accounts.mint.decimals,
+    ctx.accounts.stable_mint.decimals,
+    current_time,
+  )?;
+  check_invariants(
+    pool,
+    &mut ctx.accounts.treasury,
+    &mut ctx.accounts.stable_treasury,
+    &mut ctx.accounts.base_treasury,
+    &mut ctx.accounts.stable_mint,
+    surpluses,
+  )?;
+  // Schedule the next slice, or close the order once the escrow is empty
+  dca_order.reference_price = pre_price;
+  dca_order.next_execution_time = current_time
+    .checked_add(dca_order.interval)
+    .ok_or(ErrorCode::MathOverflow)?;
+  ctx.accounts.escrow.reload()?;
+  if !(ctx.accounts.escrow.amount > 0) {
+    let escrow_token_program = if bid_base {
+      ctx.accounts.base_token_program.to_account_info()
+    } else {
+      ctx.accounts.pool_token_program.to_account_info()
+    };
+    token_interface::close_account(CpiContext::new_with_signer(
+      escrow_token_program,
+      token_interface::CloseAccount {
+        account: ctx.accounts.escrow.to_account_info(),
+        destination: ctx.accounts.authority.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ))?;
+    dca_order.close(ctx.accounts.authority.to_account_info())?;
+    ctx.accounts.pool.open_orders = ctx
+      .accounts
+      .pool
+      .open_orders
+      .checked_sub(1)
+      .ok_or(ErrorCode::MathOverflow)?;
+  }
+  Ok(())
+}
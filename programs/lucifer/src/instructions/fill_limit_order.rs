@@ -0,0 +1,198 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct FillLimitOrder<'info> {
+  #[account(mut)]
+  pub keeper: Signer<'info>,
+  #[account(mut)]
+  /// CHECK: Order's owner, only receives tokens and rent
+  pub authority: AccountInfo<'info>,
+  // Pool's info
+  #[account(mut, has_one = lpt_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  pub lpt_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Order's info
+  #[account(
+    mut,
+    close = authority,
+    has_one = authority,
+    has_one = pool,
+    has_one = bid_mint,
+    has_one = ask_mint,
+    has_one = escrow
+  )]
+  pub limit_order: Account<'info, LimitOrder>,
+  pub bid_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub ask_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mut)]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = bid_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = bid_token_program
+  )]
+  pub bid_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = ask_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = ask_token_program
+  )]
+  pub ask_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = keeper,
+    associated_token::mint = ask_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = ask_token_program
+  )]
+  pub ask_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = keeper,
+    associated_token::mint = ask_mint,
+    associated_token::authority = keeper,
+    associated_token::token_program = ask_token_program
+  )]
+  pub keeper_ask_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub bid_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub ask_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<FillLimitOrder>) -> Result<()> {
+  let bid_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.bid_treasury)?;
+  let ask_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.ask_treasury)?;
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let limit_order = &ctx.accounts.limit_order;
+  if current_time > limit_order.expiry_time {
+    return err!(ErrorCode::OrderExpired);
+  }
+  let pool = &mut ctx.accounts.pool;
+  check_active(pool)?;
+  check_trading_halt(pool, current_time)?;
+  let pre_price = pool.calc_spot_price().ok_or(ErrorCode::MathOverflow)?;
+  let pre_invariant = pool
+    .calc_weighted_invariant()
+    .ok_or(ErrorCode::MathOverflow)?;
+  let fee = pool
+    .calc_swap_fee(current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Pool signature
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  // Move escrowed bid token into the pool, the swap takes what the treasury received
+  let bid_amount = transfer_in(
+    &ctx.accounts.bid_token_program,
+    ctx.accounts.escrow.to_account_info(),
+    &mut ctx.accounts.bid_treasury,
+    &ctx.accounts.bid_mint,
+    ctx.accounts.treasurer.to_account_info(),
+    seeds,
+    ctx.accounts.escrow.amount,
+  )?;
+  // Swap through the pool once its price crosses the limit price
+  let bid_is_stable = limit_order.bid_mint == pool.stable_mint;
+  let supply = ctx.accounts.lpt_mint.supply;
+  let (ask_amount, _) = pool
+    .swap(bid_amount, bid_is_stable, fee, supply, current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let tip = LimitOrder::calc_keeper_tip(ask_amount).ok_or(ErrorCode::MathOverflow)?;
+  let ask_amount_after_tip = ask_amount.checked_sub(tip).ok_or(ErrorCode::MathOverflow)?;
+  // The owner's limit price holds net of the keeper tip
+  let min_ask_amount = limit_order
+    .calc_min_ask_amount()
+    .ok_or(ErrorCode::MathOverflow)?;
+  if ask_amount_after_tip < min_ask_amount {
+    return err!(ErrorCode::LimitPriceNotReached);
+  }
+  // Transfer ask token to the owner and the keeper
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.ask_token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.ask_treasury.to_account_info(),
+        mint: ctx.accounts.ask_mint.to_account_info(),
+        to: ctx.accounts.ask_token_account.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    ask_amount_after_tip,
+    ctx.accounts.ask_mint.decimals,
+  )?;
+  if tip > 0 {
+    token_interface::transfer_checked(
+      CpiContext::new_with_signer(
+        ctx.accounts.ask_token_program.to_account_info(),
+        token_interface::TransferChecked {
+          from: ctx.accounts.ask_treasury.to_account_info(),
+          mint: ctx.accounts.ask_mint.to_account_info(),
+          to: ctx.accounts.keeper_ask_token_account.to_account_info(),
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      tip,
+      ctx.accounts.ask_mint.decimals,
+    )?;
+  }
+  pool.open_orders = pool
+    .open_orders
+    .checked_sub(1)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Close escrow
+  token_interface::close_account(CpiContext::new_with_signer(
+    ctx.accounts.bid_token_program.to_account_info(),
+    token_interface::CloseAccount {
+      account: ctx.accounts.escrow.to_account_info(),
+      destination: ctx.accounts.authority.to_account_info(),
+      authority: ctx.accounts.treasurer.to_account_info(),
+    },
+    seeds,
+  ))?;
+  check_swap_invariant(pool, pre_invariant)?;
+  check_price_impact(pool, pre_price, current_time)?;
+  pool
+    .update_volatility(current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let (mint_decimals, stable_decimals) = if bid_is_stable {
+    (
+      ctx.accounts.ask_mint.decimals,
+      ctx.accounts.bid_mint.decimals,
+    )
+  } else {
+    (
+      ctx.accounts.bid_mint.decimals,
+      ctx.accounts.ask_mint.decimals,
+    )
+  };
+  check_oracle_band(
+    pool,
+    ctx.remaining_accounts,
+    mint_decimals,
+    stable_decimals,
+    current_time,
+  )?;
+  check_ledger(pool, &mut ctx.accounts.bid_treasury, bid_surplus)?;
+  check_ledger(pool, &mut ctx.accounts.ask_treasury, ask_surplus)?;
+  Ok(())
+}
@@ -0,0 +1,117 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::solana_program::sysvar::instructions::{
+  load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::InstructionData;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // One of the pool's mints
+  #[account(
+    constraint = mint.key() == pool.mint
+      || mint.key() == pool.stable_mint
+      || mint.key() == pool.base_mint @ ErrorCode::InvalidMint
+  )]
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
+  )]
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(address = sysvar::instructions::ID)]
+  /// CHECK: Instructions sysvar
+  pub instructions: AccountInfo<'info>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<FlashLoan>, amount: u64) -> Result<()> {
+  if !(amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  let pool = &mut ctx.accounts.pool;
+  check_active(pool)?;
+  if pool.flash_loan_amount > 0 {
+    return err!(ErrorCode::FlashLoanInProgress);
+  }
+  // Introspection: the loan must be a top-level instruction followed by a matching repay
+  let instructions = &ctx.accounts.instructions;
+  let current_index = load_current_index_checked(instructions)? as usize;
+  let current_ix = load_instruction_at_checked(current_index, instructions)?;
+  if current_ix.program_id != crate::ID {
+    return err!(ErrorCode::MissingFlashRepay);
+  }
+  let repay_discriminator = crate::instruction::FlashRepay {}.data();
+  let mut index = current_index + 1;
+  let mut repaid = false;
+  while let Ok(ix) = load_instruction_at_checked(index, instructions) {
+    if ix.program_id == crate::ID
+      && ix.data.len() >= 8
+      && ix.data[..8] == repay_discriminator[..8]
+      // FlashRepay accounts: authority, pool, treasurer, mint, ...
+      && ix.accounts.len() > 3
+      && ix.accounts[1].pubkey == pool.key()
+      && ix.accounts[3].pubkey == ctx.accounts.mint.key()
+    {
+      repaid = true;
+      break;
+    }
+    index += 1;
+  }
+  if !repaid {
+    return err!(ErrorCode::MissingFlashRepay);
+  }
+  // Pool actions: lend the mint
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.treasury.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.token_account.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    amount,
+    ctx.accounts.mint.decimals,
+  )?;
+  pool.flash_loan_mint = ctx.accounts.mint.key();
+  pool.flash_loan_amount = amount;
+  Ok(())
+}
@@ -0,0 +1,99 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  #[account(constraint = mint.key() == pool.flash_loan_mint @ ErrorCode::InvalidMint)]
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
+  )]
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<FlashRepay>) -> Result<()> {
+  let surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.treasury)?;
+  let pool = &mut ctx.accounts.pool;
+  if !(pool.flash_loan_amount > 0) {
+    return err!(ErrorCode::NoFlashLoan);
+  }
+  let fee = pool
+    .calc_flash_loan_fee(pool.flash_loan_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let repay_amount = pool
+    .flash_loan_amount
+    .checked_add(fee)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Wallet Actions: Transfer repay token, grossed up by any transfer fee
+  let transfer_amount = calc_transfer_amount(&ctx.accounts.mint, repay_amount)?;
+  let received = transfer_in(
+    &ctx.accounts.token_program,
+    ctx.accounts.token_account.to_account_info(),
+    &mut ctx.accounts.treasury,
+    &ctx.accounts.mint,
+    ctx.accounts.authority.to_account_info(),
+    &[],
+    transfer_amount,
+  )?;
+  if received < repay_amount {
+    return err!(ErrorCode::InsufficientRepayment);
+  }
+  // Anything received past the loan is fee
+  let fee = received
+    .checked_sub(pool.flash_loan_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  msg!("Flash loan fee {}", fee);
+  // Fee accrues to LPs
+  let mint = pool.flash_loan_mint;
+  if mint == pool.mint {
+    pool.balance = pool
+      .balance
+      .checked_add(fee)
+      .ok_or(ErrorCode::MathOverflow)?;
+  } else if mint == pool.stable_mint {
+    pool.stable_balance = pool
+      .stable_balance
+      .checked_add(fee)
+      .ok_or(ErrorCode::MathOverflow)?;
+  } else {
+    // Base fees aren't backing any stable
+    pool.lending_reserve = pool
+      .lending_reserve
+      .checked_add(fee)
+      .ok_or(ErrorCode::MathOverflow)?;
+  }
+  pool.flash_loan_mint = Pubkey::default();
+  pool.flash_loan_amount = 0;
+  check_ledger(pool, &mut ctx.accounts.treasury, surplus)?;
+  Ok(())
+}
@@ -0,0 +1,29 @@
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeReferral<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  pub pool: Account<'info, Pool>,
+  #[account(
+    init,
+    payer = authority,
+    space = Referral::LEN,
+    seeds = [b"referral".as_ref(), &pool.key().to_bytes(), &authority.key().to_bytes()],
+    bump
+  )]
+  pub referral: Account<'info, Referral>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<InitializeReferral>) -> Result<()> {
+  let referral = &mut ctx.accounts.referral;
+  referral.pool = ctx.accounts.pool.key();
+  referral.referrer = ctx.accounts.authority.key();
+  Ok(())
+}
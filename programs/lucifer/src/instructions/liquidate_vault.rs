@@ -0,0 +1,161 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct LiquidateVault<'info> {
+  // Anyone can liquidate, paying the whole debt: the principal in vault stable and the
+  // stability fee in stable
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut, has_one = base_mint, has_one = stable_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    mut,
+    seeds = [b"stable_mint".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    mut,
+    seeds = [b"vault_stable_mint".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub vault_stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = stable_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Vault's info
+  #[account(
+    has_one = pool,
+    has_one = oracle,
+    seeds = [b"vault_config".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub vault_config: Account<'info, VaultConfig>,
+  /// CHECK: Parsed as a Pyth-compatible price account
+  pub oracle: AccountInfo<'info>,
+  #[account(mut, has_one = pool, has_one = escrow)]
+  pub vault: Account<'info, Vault>,
+  #[account(mut)]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Liquidator's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = base_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = stable_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    mut,
+    associated_token::mint = vault_stable_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
+  )]
+  pub vault_stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+// The liquidator takes collateral worth the debt plus the bonus, the rest stays in the
+// vault for its owner
+pub fn exec(ctx: Context<LiquidateVault>) -> Result<()> {
+  let stable_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.stable_treasury)?;
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let vault_config = &ctx.accounts.vault_config;
+  let pool = &mut ctx.accounts.pool;
+  let vault = &mut ctx.accounts.vault;
+  vault
+    .accrue(vault_config.stability_fee, current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let collateral_value = calc_vault_collateral_value(
+    vault_config,
+    pool,
+    &ctx.accounts.oracle,
+    vault.collateral,
+    current_time,
+  )?;
+  let collateral_ratio = vault
+    .calc_collateral_ratio(collateral_value)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if collateral_ratio >= vault_config.liquidation_ratio {
+    return err!(ErrorCode::VaultHealthy);
+  }
+  let seized_amount = vault_config
+    .calc_liquidation_collateral(vault.debt, collateral_value, vault.collateral)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Repay the whole debt
+  let debt = vault.debt;
+  let (principal_amount, fee_amount) = vault.repay(debt).ok_or(ErrorCode::MathOverflow)?;
+  pool
+    .add_vault_repayment(principal_amount, fee_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  repay_vault(
+    &ctx.accounts.pool_token_program,
+    &ctx.accounts.authority,
+    &ctx.accounts.stable_mint,
+    &ctx.accounts.vault_stable_mint,
+    &ctx.accounts.vault_stable_token_account,
+    &ctx.accounts.stable_token_account,
+    &ctx.accounts.stable_treasury,
+    principal_amount,
+    fee_amount,
+  )?;
+  // Seize collateral
+  vault.collateral = vault
+    .collateral
+    .checked_sub(seized_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.base_token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.escrow.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
+        to: ctx.accounts.base_token_account.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    seized_amount,
+    ctx.accounts.base_mint.decimals,
+  )?;
+  msg!("Liquidate vault {} for {}", principal_amount, seized_amount);
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.stable_treasury,
+    stable_surplus,
+  )?;
+  Ok(())
+}
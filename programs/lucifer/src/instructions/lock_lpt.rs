@@ -0,0 +1,102 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct LockLpt<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut, has_one = lpt_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  pub lpt_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Farm's info
+  #[account(mut, has_one = pool)]
+  pub farm: Account<'info, Farm>,
+  // Lock's info
+  #[account(init, payer = authority, space = LpLock::LEN)]
+  pub lp_lock: Account<'info, LpLock>,
+  #[account(
+    init,
+    payer = authority,
+    token::mint = lpt_mint,
+    token::authority = treasurer,
+    token::token_program = pool_token_program,
+    seeds = [b"lp_lock_vault".as_ref(), &lp_lock.key().to_bytes()], bump
+  )]
+  pub vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    mut,
+    associated_token::mint = lpt_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
+  )]
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<LockLpt>, amount: u64, duration: i64) -> Result<()> {
+  if !(amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  if !(duration > 0 && duration <= MAX_LOCK_DURATION) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let pool = &mut ctx.accounts.pool;
+  let farm = &mut ctx.accounts.farm;
+  let lp_lock = &mut ctx.accounts.lp_lock;
+  farm.update(current_time).ok_or(ErrorCode::MathOverflow)?;
+  // Locked LPT earns farm rewards on its boosted weight
+  let boost = LpLock::calc_boost(duration).ok_or(ErrorCode::MathOverflow)?;
+  let weight = LpLock::calc_weight(amount, boost).ok_or(ErrorCode::MathOverflow)?;
+  lp_lock.authority = ctx.accounts.authority.key();
+  lp_lock.farm = farm.key();
+  lp_lock.vault = ctx.accounts.vault.key();
+  lp_lock.amount = amount;
+  lp_lock.boost = boost;
+  lp_lock.weight = weight;
+  lp_lock.unlock_time = current_time
+    .checked_add(duration)
+    .ok_or(ErrorCode::MathOverflow)?;
+  lp_lock.reward_debt = farm
+    .calc_reward_debt(weight)
+    .ok_or(ErrorCode::MathOverflow)?;
+  farm.total_staked = farm
+    .total_staked
+    .checked_add(weight)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.open_locks = pool
+    .open_locks
+    .checked_add(1)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Lock lpt token
+  token_interface::transfer_checked(
+    CpiContext::new(
+      ctx.accounts.pool_token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.lpt_token_account.to_account_info(),
+        mint: ctx.accounts.lpt_mint.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+      },
+    ),
+    amount,
+    ctx.accounts.lpt_mint.decimals,
+  )?;
+  Ok(())
+}
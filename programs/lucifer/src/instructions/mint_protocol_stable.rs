@@ -0,0 +1,151 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct MintProtocolStable<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Protocol's info
+  #[account(mut, has_one = stable_mint, seeds = [b"protocol".as_ref()], bump)]
+  pub protocol: Account<'info, Protocol>,
+  #[account(mut)]
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's info
+  #[account(mut, has_one = base_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    mut,
+    has_one = protocol,
+    has_one = pool,
+    seeds = [b"stable_member".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub stable_member: Account<'info, StableMember>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = base_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = base_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = stable_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Temporary wrapped SOL account, a fresh keypair that only signs with native
+  #[account(mut)]
+  /// CHECK: Created and closed within the instruction when native
+  pub wsol_account: AccountInfo<'info>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<MintProtocolStable>, base_amount: u64, native: bool) -> Result<()> {
+  let base_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.base_treasury)?;
+  check_active(&ctx.accounts.pool)?;
+  if !(base_amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  // Transfer Base Mint, only what the treasury received is backed
+  // Pay in from lamports with native
+  let wrapped = native && ctx.accounts.base_token_account.is_native();
+  let base_source = if wrapped {
+    wrap_sol(
+      &ctx.accounts.system_program,
+      &ctx.accounts.base_token_program,
+      &ctx.accounts.rent,
+      &ctx.accounts.authority,
+      &ctx.accounts.base_mint.to_account_info(),
+      &ctx.accounts.wsol_account,
+      base_amount,
+    )?;
+    ctx.accounts.wsol_account.to_account_info()
+  } else {
+    ctx.accounts.base_token_account.to_account_info()
+  };
+  let base_amount = transfer_in(
+    &ctx.accounts.base_token_program,
+    base_source,
+    &mut ctx.accounts.base_treasury,
+    &ctx.accounts.base_mint,
+    ctx.accounts.authority.to_account_info(),
+    &[],
+    base_amount,
+  )?;
+  if wrapped {
+    unwrap_sol(
+      &ctx.accounts.base_token_program,
+      &ctx.accounts.authority,
+      &ctx.accounts.wsol_account,
+    )?;
+  }
+  let protocol = &mut ctx.accounts.protocol;
+  let pool = &mut ctx.accounts.pool;
+  let stable_member = &mut ctx.accounts.stable_member;
+  let stable_amount = protocol
+    .calc_stable_amount(base_amount, pool.base_decimals)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Pool's share of the protocol stable
+  stable_member.minted = stable_member
+    .minted
+    .checked_add(stable_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if stable_member.minted > stable_member.debt_ceiling {
+    return err!(ErrorCode::DebtCeilingExceeded);
+  }
+  protocol.total_minted = protocol
+    .total_minted
+    .checked_add(stable_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.protocol_backing = pool
+    .protocol_backing
+    .checked_add(base_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Mint Stable
+  let seeds: &[&[&[u8]]] = &[&["protocol".as_ref(), &[ctx.bumps.protocol]]];
+  token_interface::mint_to(
+    CpiContext::new_with_signer(
+      ctx.accounts.token_program.to_account_info(),
+      token_interface::MintTo {
+        to: ctx.accounts.stable_token_account.to_account_info(),
+        mint: ctx.accounts.stable_mint.to_account_info(),
+        authority: protocol.to_account_info(),
+      },
+      seeds,
+    ),
+    stable_amount,
+  )?;
+  msg!("mint to protocol stable {}", stable_amount);
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.base_treasury,
+    base_surplus,
+  )?;
+  Ok(())
+}
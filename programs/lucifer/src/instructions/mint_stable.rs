@@ -1,97 +1,154 @@
+use crate::errors::ErrorCode;
 use crate::schema::pool::*;
+use crate::utils::*;
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 
 #[derive(Accounts)]
 pub struct MintStable<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
   // Pool's info
-  #[account(mut, has_one=base_mint)]
+  #[account(mut, has_one = base_mint, has_one = stable_mint)]
   pub pool: Account<'info, Pool>,
   #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
   /// CHECK: Just a pure account
   pub treasurer: AccountInfo<'info>,
   // Pool's Mints
-  pub base_mint: Box<Account<'info, token::Mint>>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
   #[account(
     mut,
     seeds = [b"stable_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub stable_mint: Account<'info, token::Mint>,
+  pub stable_mint: InterfaceAccount<'info, token_interface::Mint>,
   // Pool's token account
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
   )]
-  pub base_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = stable_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Wallet's Token Accounts
   #[account(
-    mut,
+    init_if_needed,
+    payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
   )]
-  pub base_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = stable_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Temporary wrapped SOL account, a fresh keypair that only signs with native
+  #[account(mut)]
+  /// CHECK: Created and closed within the instruction when native
+  pub wsol_account: AccountInfo<'info>,
 
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn exec(ctx: Context<MintStable>, base_amount: u64) -> Result<()> {
-  let pool = &mut ctx.accounts.pool;
-
-  // Transfer Base Mint
+pub fn exec(ctx: Context<MintStable>, base_amount: u64, native: bool) -> Result<()> {
+  let stable_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.stable_treasury)?;
+  let base_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.base_treasury)?;
+  check_active(&ctx.accounts.pool)?;
+  // Transfer Base Mint, straight from lamports with native
+  let mut base_received = 0;
   if base_amount > 0 {
-    token::transfer(
-      CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
-          from: ctx.accounts.base_token_account.to_account_info(),
-          to: ctx.accounts.base_treasury.to_account_info(),
-          authority: ctx.accounts.authority.to_account_info(),
-        },
-      ),
+    let wrapped = native && ctx.accounts.base_token_account.is_native();
+    let base_source = if wrapped {
+      wrap_sol(
+        &ctx.accounts.system_program,
+        &ctx.accounts.base_token_program,
+        &ctx.accounts.rent,
+        &ctx.accounts.authority,
+        &ctx.accounts.base_mint.to_account_info(),
+        &ctx.accounts.wsol_account,
+        base_amount,
+      )?;
+      ctx.accounts.wsol_account.to_account_info()
+    } else {
+      ctx.accounts.base_token_account.to_account_info()
+    };
+    base_received = transfer_in(
+      &ctx.accounts.base_token_program,
+      base_source,
+      &mut ctx.accounts.base_treasury,
+      &ctx.accounts.base_mint,
+      ctx.accounts.authority.to_account_info(),
+      &[],
       base_amount,
     )?;
+    if wrapped {
+      unwrap_sol(
+        &ctx.accounts.base_token_program,
+        &ctx.accounts.authority,
+        &ctx.accounts.wsol_account,
+      )?;
+    }
   }
   // Mint Stable
+  let pool = &mut ctx.accounts.pool;
+  let stable_amount = pool
+    .calc_stable_amount(base_received)
+    .ok_or(ErrorCode::MathOverflow)?;
   let seeds: &[&[&[u8]]] = &[&[
     "treasurer".as_ref(),
     &pool.key().to_bytes(),
-    &[*ctx.bumps.get("treasurer").unwrap()],
+    &[ctx.bumps.treasurer],
   ]];
 
   let mint_to_stable = CpiContext::new_with_signer(
-    ctx.accounts.token_program.to_account_info(),
-    token::MintTo {
+    ctx.accounts.pool_token_program.to_account_info(),
+    token_interface::MintTo {
       to: ctx.accounts.stable_token_account.to_account_info(),
       mint: ctx.accounts.stable_mint.to_account_info(),
       authority: ctx.accounts.treasurer.to_account_info(),
     },
     seeds,
   );
-  token::mint_to(mint_to_stable, base_amount)?;
-  msg!("mint to stable {}", base_amount);
+  token_interface::mint_to(mint_to_stable, stable_amount)?;
+  msg!("mint to stable {}", stable_amount);
 
   // Update Pool info
-  pool.base_balance += base_amount;
+  pool.base_balance = pool
+    .base_balance
+    .checked_add(base_received)
+    .ok_or(ErrorCode::MathOverflow)?;
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.stable_treasury,
+    stable_surplus,
+  )?;
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.base_treasury,
+    base_surplus,
+  )?;
+  check_stable_backing(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.stable_mint,
+    &mut ctx.accounts.base_treasury,
+  )?;
   Ok(())
 }
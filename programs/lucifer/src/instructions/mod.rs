@@ -24,3 +24,87 @@ pub use buy::*;
 
 pub mod sell;
 pub use sell::*;
+
+pub mod stake_farm;
+pub use stake_farm::*;
+
+pub mod unstake_farm;
+pub use unstake_farm::*;
+
+pub mod claim_farm;
+pub use claim_farm::*;
+
+pub mod flash_loan;
+pub use flash_loan::*;
+
+pub mod flash_repay;
+pub use flash_repay::*;
+
+pub mod place_limit_order;
+pub use place_limit_order::*;
+
+pub mod fill_limit_order;
+pub use fill_limit_order::*;
+
+pub mod cancel_limit_order;
+pub use cancel_limit_order::*;
+
+pub mod place_dca_order;
+pub use place_dca_order::*;
+
+pub mod execute_dca_order;
+pub use execute_dca_order::*;
+
+pub mod cancel_dca_order;
+pub use cancel_dca_order::*;
+
+pub mod lock_lpt;
+pub use lock_lpt::*;
+
+pub mod claim_lp_lock;
+pub use claim_lp_lock::*;
+
+pub mod unlock_lpt;
+pub use unlock_lpt::*;
+
+pub mod initialize_referral;
+pub use initialize_referral::*;
+
+pub mod claim_referral;
+pub use claim_referral::*;
+
+pub mod sync;
+pub use sync::*;
+
+pub mod view_base_ledger;
+pub use view_base_ledger::*;
+
+pub mod view_solvency;
+pub use view_solvency::*;
+
+pub mod mint_protocol_stable;
+pub use mint_protocol_stable::*;
+
+pub mod burn_protocol_stable;
+pub use burn_protocol_stable::*;
+
+pub mod open_vault;
+pub use open_vault::*;
+
+pub mod deposit_vault;
+pub use deposit_vault::*;
+
+pub mod withdraw_vault;
+pub use withdraw_vault::*;
+
+pub mod liquidate_vault;
+pub use liquidate_vault::*;
+
+pub mod close_vault;
+pub use close_vault::*;
+
+pub mod stake_gov;
+pub use stake_gov::*;
+
+pub mod unstake_gov;
+pub use unstake_gov::*;
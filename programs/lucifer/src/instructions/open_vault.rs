@@ -0,0 +1,62 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+
+#[derive(Accounts)]
+pub struct OpenVault<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = base_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    has_one = pool,
+    seeds = [b"vault_config".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub vault_config: Account<'info, VaultConfig>,
+  #[account(
+    init,
+    payer = authority,
+    space = Vault::LEN,
+    seeds = [b"vault".as_ref(), &pool.key().to_bytes(), &authority.key().to_bytes()], bump
+  )]
+  pub vault: Account<'info, Vault>,
+  #[account(
+    init,
+    payer = authority,
+    token::mint = base_mint,
+    token::authority = treasurer,
+    token::token_program = base_token_program,
+    seeds = [b"vault_escrow".as_ref(), &vault.key().to_bytes()], bump
+  )]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<OpenVault>) -> Result<()> {
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let vault = &mut ctx.accounts.vault;
+  vault.authority = ctx.accounts.authority.key();
+  vault.pool = ctx.accounts.pool.key();
+  vault.escrow = ctx.accounts.escrow.key();
+  vault.collateral = 0;
+  vault.principal = 0;
+  vault.debt = 0;
+  vault.last_accrued_at = current_time;
+  let pool = &mut ctx.accounts.pool;
+  pool.open_vaults = pool
+    .open_vaults
+    .checked_add(1)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(())
+}
@@ -0,0 +1,105 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct PlaceDcaOrder<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // The stable mint or the base mint
+  #[account(
+    constraint = bid_mint.key() == pool.stable_mint
+      || bid_mint.key() == pool.base_mint @ ErrorCode::InvalidMint
+  )]
+  pub bid_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Order's info
+  #[account(init, payer = authority, space = DcaOrder::LEN)]
+  pub dca_order: Account<'info, DcaOrder>,
+  #[account(
+    init,
+    payer = authority,
+    token::mint = bid_mint,
+    token::authority = treasurer,
+    token::token_program = token_program,
+    seeds = [b"dca_order_escrow".as_ref(), &dca_order.key().to_bytes()], bump
+  )]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    mut,
+    associated_token::mint = bid_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub bid_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(
+  ctx: Context<PlaceDcaOrder>,
+  amount: u64,
+  amount_per_interval: u64,
+  interval: i64,
+  max_slippage: u64,
+) -> Result<()> {
+  if !(amount > 0 && amount_per_interval > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  check_active(&ctx.accounts.pool)?;
+  if !(interval > 0) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  if max_slippage > PRECISION_U64 {
+    return err!(ErrorCode::Slippage);
+  }
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let dca_order = &mut ctx.accounts.dca_order;
+  dca_order.authority = ctx.accounts.authority.key();
+  dca_order.pool = ctx.accounts.pool.key();
+  dca_order.bid_mint = ctx.accounts.bid_mint.key();
+  dca_order.escrow = ctx.accounts.escrow.key();
+  dca_order.amount_per_interval = amount_per_interval;
+  dca_order.interval = interval;
+  dca_order.next_execution_time = current_time;
+  dca_order.max_slippage = max_slippage;
+  dca_order.reference_price = ctx
+    .accounts
+    .pool
+    .calc_spot_price()
+    .ok_or(ErrorCode::MathOverflow)?;
+  let pool = &mut ctx.accounts.pool;
+  pool.open_orders = pool
+    .open_orders
+    .checked_add(1)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Escrow bid token
+  token_interface::transfer_checked(
+    CpiContext::new(
+      ctx.accounts.token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.bid_token_account.to_account_info(),
+        mint: ctx.accounts.bid_mint.to_account_info(),
+        to: ctx.accounts.escrow.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+      },
+    ),
+    amount,
+    ctx.accounts.bid_mint.decimals,
+  )?;
+  Ok(())
+}
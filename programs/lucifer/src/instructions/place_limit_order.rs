@@ -0,0 +1,96 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct PlaceLimitOrder<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Order's mints, the pool mint against the stable mint
+  #[account(
+    constraint = bid_mint.key() == pool.mint
+      || bid_mint.key() == pool.stable_mint @ ErrorCode::InvalidMint
+  )]
+  pub bid_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    constraint = (bid_mint.key() == pool.mint && ask_mint.key() == pool.stable_mint)
+      || (bid_mint.key() == pool.stable_mint && ask_mint.key() == pool.mint) @ ErrorCode::InvalidMint
+  )]
+  pub ask_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Order's info
+  #[account(init, payer = authority, space = LimitOrder::LEN)]
+  pub limit_order: Account<'info, LimitOrder>,
+  #[account(
+    init,
+    payer = authority,
+    token::mint = bid_mint,
+    token::authority = treasurer,
+    token::token_program = token_program,
+    seeds = [b"limit_order_escrow".as_ref(), &limit_order.key().to_bytes()], bump
+  )]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    mut,
+    associated_token::mint = bid_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub bid_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(
+  ctx: Context<PlaceLimitOrder>,
+  bid_amount: u64,
+  limit_price: u64,
+  expiry_time: i64,
+) -> Result<()> {
+  if !(bid_amount > 0 && limit_price > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  check_active(&ctx.accounts.pool)?;
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  if !(expiry_time > current_time) {
+    return err!(ErrorCode::InvalidTimeRange);
+  }
+  // Escrow bid token, the order holds what the escrow received
+  let bid_amount = transfer_in(
+    &ctx.accounts.token_program,
+    ctx.accounts.bid_token_account.to_account_info(),
+    &mut ctx.accounts.escrow,
+    &ctx.accounts.bid_mint,
+    ctx.accounts.authority.to_account_info(),
+    &[],
+    bid_amount,
+  )?;
+  let limit_order = &mut ctx.accounts.limit_order;
+  limit_order.authority = ctx.accounts.authority.key();
+  limit_order.pool = ctx.accounts.pool.key();
+  limit_order.bid_mint = ctx.accounts.bid_mint.key();
+  limit_order.ask_mint = ctx.accounts.ask_mint.key();
+  limit_order.escrow = ctx.accounts.escrow.key();
+  limit_order.bid_amount = bid_amount;
+  limit_order.limit_price = limit_price;
+  limit_order.expiry_time = expiry_time;
+  let pool = &mut ctx.accounts.pool;
+  pool.open_orders = pool
+    .open_orders
+    .checked_add(1)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(())
+}
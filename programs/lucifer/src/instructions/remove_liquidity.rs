@@ -1,155 +1,258 @@
+use crate::errors::ErrorCode;
 use crate::oracle::*;
 use crate::schema::pool::*;
+use crate::utils::*;
 
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 
 #[derive(Accounts)]
 pub struct RemoveLiquidity<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
   // Pool's info
-  #[account(mut, has_one=mint)]
+  #[account(
+    mut,
+    has_one = mint,
+    has_one = base_mint,
+    has_one = stable_mint,
+    has_one = lpt_mint
+  )]
   pub pool: Account<'info, Pool>,
   #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
   /// CHECK: Just a pure account
   pub treasurer: AccountInfo<'info>,
   // Pool's Mints
-  pub mint: Box<Account<'info, token::Mint>>,
-  pub base_mint: Box<Account<'info, token::Mint>>,
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
   #[account(
     mut,
     seeds = [b"stable_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub stable_mint: Account<'info, token::Mint>,
+  pub stable_mint: InterfaceAccount<'info, token_interface::Mint>,
   #[account(
     mut,
     seeds = [b"lpt_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub lpt_mint: Account<'info, token::Mint>,
+  pub lpt_mint: InterfaceAccount<'info, token_interface::Mint>,
   // Pool's token account
   #[account(
     mut,
     associated_token::mint = mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
   )]
-  pub treasury: Box<Account<'info, token::TokenAccount>>,
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = stable_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = base_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
   )]
-  pub base_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = lpt_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Wallet's Token Accounts
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
   )]
-  pub token_account: Box<Account<'info, token::TokenAccount>>,
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
   )]
-  pub base_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = lpt_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Temporary wrapped SOL account, a fresh keypair that only signs with native
+  #[account(mut)]
+  /// CHECK: Created and closed within the instruction when native
+  pub wsol_account: AccountInfo<'info>,
 
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn exec(ctx: Context<RemoveLiquidity>, lpt_amount: u64) -> Result<()> {
+pub fn exec(ctx: Context<RemoveLiquidity>, lpt_amount: u64, native: bool) -> Result<()> {
+  let surpluses = calc_surpluses(
+    &ctx.accounts.pool,
+    &ctx.accounts.treasury,
+    &ctx.accounts.stable_treasury,
+    &ctx.accounts.base_treasury,
+  )?;
   let pool = &mut ctx.accounts.pool;
   if !(lpt_amount > 0) {
     return Ok(());
   }
   // Burn token
   let burn_lpt = CpiContext::new(
-    ctx.accounts.token_program.to_account_info(),
-    token::Burn {
+    ctx.accounts.pool_token_program.to_account_info(),
+    token_interface::Burn {
       from: ctx.accounts.lpt_token_account.to_account_info(),
       mint: ctx.accounts.lpt_mint.to_account_info(),
       authority: ctx.accounts.authority.to_account_info(),
     },
   );
-  token::burn(burn_lpt, lpt_amount)?;
+  token_interface::burn(burn_lpt, lpt_amount)?;
   // Update pool info
+  // Proportional exit is the same for every curve
   let reserves = vec![pool.balance, pool.stable_balance];
   let supply = ctx.accounts.lpt_mint.supply;
-  let amounts = calc_mint_receives_remove_full_side(lpt_amount, supply, &reserves).unwrap();
+  let amounts = calc_mint_receives_remove_full_side(lpt_amount, supply, &reserves)
+    .ok_or(ErrorCode::MathOverflow)?;
   let amount = amounts[0];
   let stable_amount = amounts[1];
 
-  pool.balance -= amount;
-  pool.stable_balance -= stable_amount;
+  pool.balance = pool
+    .balance
+    .checked_sub(amount)
+    .ok_or(ErrorCode::InsufficientLiquidity)?;
+  pool.stable_balance = pool
+    .stable_balance
+    .checked_sub(stable_amount)
+    .ok_or(ErrorCode::InsufficientLiquidity)?;
+  pool.lpt_supply = pool
+    .lpt_supply
+    .checked_sub(lpt_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
   // Pool signature
   let seeds: &[&[&[u8]]] = &[&[
     "treasurer".as_ref(),
     &pool.key().to_bytes(),
-    &[*ctx.bumps.get("treasurer").unwrap()],
+    &[ctx.bumps.treasurer],
   ]];
   // Burn token stable of pool
   let burn_stable = CpiContext::new_with_signer(
-    ctx.accounts.token_program.to_account_info(),
-    token::Burn {
+    ctx.accounts.pool_token_program.to_account_info(),
+    token_interface::Burn {
       from: ctx.accounts.stable_treasury.to_account_info(),
       mint: ctx.accounts.stable_mint.to_account_info(),
       authority: ctx.accounts.treasurer.to_account_info(),
     },
     seeds,
   );
-  token::burn(burn_stable, stable_amount)?;
+  token_interface::burn(burn_stable, stable_amount)?;
   // Transfer Mint
-  token::transfer(
+  // Pay out as lamports with native
+  let wrapped = native && ctx.accounts.token_account.is_native();
+  let destination = if wrapped {
+    wrap_sol(
+      &ctx.accounts.system_program,
+      &ctx.accounts.token_program,
+      &ctx.accounts.rent,
+      &ctx.accounts.authority,
+      &ctx.accounts.mint.to_account_info(),
+      &ctx.accounts.wsol_account,
+      0,
+    )?;
+    ctx.accounts.wsol_account.to_account_info()
+  } else {
+    ctx.accounts.token_account.to_account_info()
+  };
+  token_interface::transfer_checked(
     CpiContext::new_with_signer(
       ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
+      token_interface::TransferChecked {
         from: ctx.accounts.treasury.to_account_info(),
-        to: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: destination,
         authority: ctx.accounts.treasurer.to_account_info(),
       },
       seeds,
     ),
     amount,
+    ctx.accounts.mint.decimals,
   )?;
+  if wrapped {
+    unwrap_sol(
+      &ctx.accounts.token_program,
+      &ctx.accounts.authority,
+      &ctx.accounts.wsol_account,
+    )?;
+  }
   // Transfer base token for user
-  token::transfer(
+  let base_amount = pool
+    .calc_base_amount(stable_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let base_wrapped = native && ctx.accounts.base_token_account.is_native();
+  let base_destination = if base_wrapped {
+    wrap_sol(
+      &ctx.accounts.system_program,
+      &ctx.accounts.base_token_program,
+      &ctx.accounts.rent,
+      &ctx.accounts.authority,
+      &ctx.accounts.base_mint.to_account_info(),
+      &ctx.accounts.wsol_account,
+      0,
+    )?;
+    ctx.accounts.wsol_account.to_account_info()
+  } else {
+    ctx.accounts.base_token_account.to_account_info()
+  };
+  token_interface::transfer_checked(
     CpiContext::new_with_signer(
-      ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
+      ctx.accounts.base_token_program.to_account_info(),
+      token_interface::TransferChecked {
         from: ctx.accounts.base_treasury.to_account_info(),
-        to: ctx.accounts.base_token_account.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
+        to: base_destination,
         authority: ctx.accounts.treasurer.to_account_info(),
       },
       seeds,
     ),
-    stable_amount,
+    base_amount,
+    ctx.accounts.base_mint.decimals,
+  )?;
+  if base_wrapped {
+    unwrap_sol(
+      &ctx.accounts.base_token_program,
+      &ctx.accounts.authority,
+      &ctx.accounts.wsol_account,
+    )?;
+  }
+  pool.base_balance = pool
+    .base_balance
+    .checked_sub(base_amount)
+    .ok_or(ErrorCode::InsufficientLiquidity)?;
+  check_invariants(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.treasury,
+    &mut ctx.accounts.stable_treasury,
+    &mut ctx.accounts.base_treasury,
+    &mut ctx.accounts.stable_mint,
+    surpluses,
   )?;
-  pool.base_balance -= stable_amount;
 
   Ok(())
 }
@@ -1,8 +1,9 @@
 use crate::errors::ErrorCode;
 use crate::f64_trait::F64Trait;
 use crate::schema::*;
+use crate::utils::*;
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 use num_traits::ToPrimitive;
 
 #[derive(Accounts)]
@@ -10,98 +11,183 @@ pub struct Repay<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
   // Pool's info
-  #[account(mut)]
+  #[account(mut, has_one = base_mint, has_one = lpt_mint)]
   pub pool: Account<'info, Pool>,
   #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
   /// CHECK: Just a pure account
   pub treasurer: AccountInfo<'info>,
   // Pool's Mints
-  #[account(mut)]
-  pub base_mint: Account<'info, token::Mint>,
+  pub base_mint: InterfaceAccount<'info, token_interface::Mint>,
   #[account(
     mut,
     seeds = [b"lpt_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub lpt_mint: Account<'info, token::Mint>,
+  pub lpt_mint: InterfaceAccount<'info, token_interface::Mint>,
   // Pool's token account
   #[account(
     mut,
     associated_token::mint = base_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
   )]
-  pub base_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = lpt_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Wallet's Token Accounts
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
   )]
-  pub base_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = lpt_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Instruction Data
   #[account(
-    init_if_needed,
-    payer = authority,
-    space = Cheque::LEN,
+    mut,
+    has_one = authority,
+    has_one = pool,
     seeds = [b"cheque".as_ref(), &pool.key().to_bytes(), &authority.key().to_bytes()], bump
   )]
   pub cheque: Account<'info, Cheque>,
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
 
 pub fn exec(ctx: Context<Repay>) -> Result<()> {
+  let base_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.base_treasury)?;
   let pool = &mut ctx.accounts.pool;
   let cheque = &mut ctx.accounts.cheque;
+  if !(cheque.borrow_amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
 
-  // Wallet Actions: Transfer repay token
-  token::transfer(
-    CpiContext::new(
-      ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
-        from: ctx.accounts.base_token_account.to_account_info(),
-        to: ctx.accounts.base_treasury.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
-      },
-    ),
-    cheque.base_amount,
+  // Wallet Actions: Transfer repay token, grossed up by any transfer fee
+  let transfer_amount = calc_transfer_amount(&ctx.accounts.base_mint, cheque.base_amount)?;
+  let received = transfer_in(
+    &ctx.accounts.base_token_program,
+    ctx.accounts.base_token_account.to_account_info(),
+    &mut ctx.accounts.base_treasury,
+    &ctx.accounts.base_mint,
+    ctx.accounts.authority.to_account_info(),
+    &[],
+    transfer_amount,
   )?;
+  if received < cheque.base_amount {
+    return err!(ErrorCode::InsufficientRepayment);
+  }
+  pool
+    .add_repayment(cheque.base_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Rounding of the fee can land a little more, which backs no stable
+  let excess = received
+    .checked_sub(cheque.base_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.lending_reserve = pool
+    .lending_reserve
+    .checked_add(excess)
+    .ok_or(ErrorCode::MathOverflow)?;
   cheque.base_amount = 0;
   // Pool Actions: Transfer lpt token
 
   let seeds: &[&[&[u8]]] = &[&[
     "treasurer".as_ref(),
     &pool.key().to_bytes(),
-    &[*ctx.bumps.get("treasurer").unwrap()],
+    &[ctx.bumps.treasurer],
   ]];
-  token::transfer(
+  token_interface::transfer_checked(
     CpiContext::new_with_signer(
-      ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
+      ctx.accounts.pool_token_program.to_account_info(),
+      token_interface::TransferChecked {
         from: ctx.accounts.lpt_treasury.to_account_info(),
+        mint: ctx.accounts.lpt_mint.to_account_info(),
         to: ctx.accounts.lpt_token_account.to_account_info(),
         authority: ctx.accounts.treasurer.to_account_info(),
       },
       seeds,
     ),
     cheque.borrow_amount,
+    ctx.accounts.lpt_mint.decimals,
   )?;
   cheque.borrow_amount = 0;
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.base_treasury,
+    base_surplus,
+  )?;
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+  use anchor_lang::error::ErrorCode as AnchorErrorCode;
+
+  #[test]
+  fn accepts_own_cheque() {
+    let fixture = PoolFixture::new();
+    let authority = Pubkey::new_unique();
+    let cheque = fixture.cheque_info(authority, fixture.key);
+    let accounts = fixture.cheque_accounts(authority, fixture.pool.base_mint, cheque);
+    assert_eq!(constraint_error::<Repay>(&accounts), None);
+  }
+
+  #[test]
+  fn rejects_foreign_cheques() {
+    let fixture = PoolFixture::new();
+    let other = PoolFixture::new();
+    let authority = Pubkey::new_unique();
+    // A cheque of another pool
+    let cheque = other.cheque_info(authority, other.key);
+    let accounts = fixture.cheque_accounts(authority, fixture.pool.base_mint, cheque);
+    assert_eq!(
+      constraint_error::<Repay>(&accounts),
+      Some(AnchorErrorCode::ConstraintSeeds.into())
+    );
+    // A cheque of another wallet
+    let cheque = fixture.cheque_info(Pubkey::new_unique(), fixture.key);
+    let accounts = fixture.cheque_accounts(authority, fixture.pool.base_mint, cheque);
+    assert_eq!(
+      constraint_error::<Repay>(&accounts),
+      Some(AnchorErrorCode::ConstraintSeeds.into())
+    );
+    // A cheque at this pool's address that names another pool
+    let cheque = fixture.cheque_info(authority, other.key);
+    let accounts = fixture.cheque_accounts(authority, fixture.pool.base_mint, cheque);
+    assert_eq!(
+      constraint_error::<Repay>(&accounts),
+      Some(AnchorErrorCode::ConstraintHasOne.into())
+    );
+  }
+
+  #[test]
+  fn rejects_foreign_base_mint() {
+    let fixture = PoolFixture::new();
+    let authority = Pubkey::new_unique();
+    let cheque = fixture.cheque_info(authority, fixture.key);
+    let accounts = fixture.cheque_accounts(authority, Pubkey::new_unique(), cheque);
+    assert_eq!(
+      constraint_error::<Repay>(&accounts),
+      Some(AnchorErrorCode::ConstraintHasOne.into())
+    );
+  }
+}
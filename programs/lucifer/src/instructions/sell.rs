@@ -1,143 +1,233 @@
-use crate::oracle::*;
+use crate::errors::ErrorCode;
 use crate::schema::*;
+use crate::utils::*;
 
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token, token};
+use anchor_spl::{associated_token, token_interface};
 
 #[derive(Accounts)]
 pub struct Sell<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
   // Pool's info
-  #[account(mut, has_one=mint)]
+  #[account(
+    mut,
+    has_one = mint,
+    has_one = base_mint,
+    has_one = stable_mint,
+    has_one = lpt_mint
+  )]
   pub pool: Account<'info, Pool>,
   #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
   /// CHECK: Just a pure account
   pub treasurer: AccountInfo<'info>,
   // Pool's Mints
-  pub mint: Box<Account<'info, token::Mint>>,
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
   #[account(
     mut,
     seeds = [b"stable_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub stable_mint: Account<'info, token::Mint>,
-  pub base_mint: Box<Account<'info, token::Mint>>,
+  pub stable_mint: InterfaceAccount<'info, token_interface::Mint>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
   #[account(
     mut,
     seeds = [b"lpt_mint".as_ref(), &pool.key().to_bytes()], bump
   )]
-  pub lpt_mint: Account<'info, token::Mint>,
+  pub lpt_mint: InterfaceAccount<'info, token_interface::Mint>,
   // Pool's token account
   #[account(
     mut,
     associated_token::mint = mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
   )]
-  pub treasury: Box<Account<'info, token::TokenAccount>>,
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = stable_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
   )]
-  pub base_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     mut,
     associated_token::mint = lpt_mint,
-    associated_token::authority = treasurer
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_treasury: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   // Wallet's Token Accounts
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
   )]
-  pub token_account: Box<Account<'info, token::TokenAccount>>,
+  pub token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = stable_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub stable_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = base_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
   )]
-  pub base_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
   #[account(
     init_if_needed,
     payer = authority,
     associated_token::mint = lpt_mint,
-    associated_token::authority = authority
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
   )]
-  pub lpt_token_account: Box<Account<'info, token::TokenAccount>>,
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Temporary wrapped SOL account, a fresh keypair that only signs with native
+  #[account(mut)]
+  /// CHECK: Created and closed within the instruction when native
+  pub wsol_account: AccountInfo<'info>,
 
   // programs
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, token::Token>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
   pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn exec(ctx: Context<Sell>, amount: u64) -> Result<()> {
-  //
-  let pool = &mut ctx.accounts.pool;
-  // Transfer Mint
-  let stable_amount =
-    calc_ask_amount_swap(amount, pool.balance, pool.stable_balance, pool.fee).unwrap();
-  let stable_amount_ignore_fee =
-    calc_ask_amount_swap(amount, pool.balance, pool.stable_balance, 0).unwrap();
-
+pub fn exec<'info>(
+  ctx: Context<'_, '_, 'info, 'info, Sell<'info>>,
+  amount: u64,
+  native: bool,
+) -> Result<()> {
+  let surpluses = calc_surpluses(
+    &ctx.accounts.pool,
+    &ctx.accounts.treasury,
+    &ctx.accounts.stable_treasury,
+    &ctx.accounts.base_treasury,
+  )?;
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  check_active(&ctx.accounts.pool)?;
   // Transfer Mint
-  token::transfer(
-    CpiContext::new(
-      ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
-        from: ctx.accounts.token_account.to_account_info(),
-        to: ctx.accounts.treasury.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
-      },
-    ),
+  // Pay in from lamports with native
+  let wrapped = native && ctx.accounts.token_account.is_native();
+  let source = if wrapped {
+    wrap_sol(
+      &ctx.accounts.system_program,
+      &ctx.accounts.token_program,
+      &ctx.accounts.rent,
+      &ctx.accounts.authority,
+      &ctx.accounts.mint.to_account_info(),
+      &ctx.accounts.wsol_account,
+      amount,
+    )?;
+    ctx.accounts.wsol_account.to_account_info()
+  } else {
+    ctx.accounts.token_account.to_account_info()
+  };
+  let amount = transfer_in(
+    &ctx.accounts.token_program,
+    source,
+    &mut ctx.accounts.treasury,
+    &ctx.accounts.mint,
+    ctx.accounts.authority.to_account_info(),
+    &[],
     amount,
   )?;
-
-  pool.balance += amount;
-  pool.stable_balance -= stable_amount;
+  if wrapped {
+    unwrap_sol(
+      &ctx.accounts.token_program,
+      &ctx.accounts.authority,
+      &ctx.accounts.wsol_account,
+    )?;
+  }
+  let pool = &mut ctx.accounts.pool;
+  check_trading_halt(pool, current_time)?;
+  let pre_price = pool.calc_spot_price().ok_or(ErrorCode::MathOverflow)?;
+  let pre_invariant = pool
+    .calc_weighted_invariant()
+    .ok_or(ErrorCode::MathOverflow)?;
+  let fee = calc_discounted_fee(
+    ctx.program_id,
+    &pool.key(),
+    &ctx.accounts.authority.key(),
+    ctx.remaining_accounts,
+    pool
+      .calc_swap_fee(current_time)
+      .ok_or(ErrorCode::MathOverflow)?,
+  )?;
+  // Swap mint for stable
+  let supply = ctx.accounts.lpt_mint.supply;
+  let (stable_amount, fee_amount) = pool
+    .swap(amount, false, fee, supply, current_time)
+    .ok_or(ErrorCode::InsufficientLiquidity)?;
+  credit_referral_swap(
+    ctx.program_id,
+    pool,
+    &ctx.accounts.authority.key(),
+    ctx.remaining_accounts,
+    fee_amount,
+    true,
+    stable_amount,
+  )?;
 
   // Transfer Mint
   let seeds: &[&[&[u8]]] = &[&[
     "treasurer".as_ref(),
     &pool.key().to_bytes(),
-    &[*ctx.bumps.get("treasurer").unwrap()],
+    &[ctx.bumps.treasurer],
   ]];
-  token::transfer(
+  token_interface::transfer_checked(
     CpiContext::new_with_signer(
-      ctx.accounts.token_program.to_account_info(),
-      token::Transfer {
+      ctx.accounts.pool_token_program.to_account_info(),
+      token_interface::TransferChecked {
         from: ctx.accounts.stable_treasury.to_account_info(),
+        mint: ctx.accounts.stable_mint.to_account_info(),
         to: ctx.accounts.stable_token_account.to_account_info(),
         authority: ctx.accounts.treasurer.to_account_info(),
       },
       seeds,
     ),
     stable_amount,
+    ctx.accounts.stable_mint.decimals,
+  )?;
+  check_swap_invariant(pool, pre_invariant)?;
+  check_price_impact(pool, pre_price, current_time)?;
+  pool
+    .update_volatility(current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Oracle guard band on the post-trade price
+  check_oracle_band(
+    pool,
+    ctx.remaining_accounts,
+    ctx.accounts.mint.decimals,
+    ctx.accounts.stable_mint.decimals,
+    current_time,
+  )?;
+  check_invariants(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.treasury,
+    &mut ctx.accounts.stable_treasury,
+    &mut ctx.accounts.base_treasury,
+    &mut ctx.accounts.stable_mint,
+    surpluses,
   )?;
-  // Update Fee
-  let amounts = vec![0, stable_amount_ignore_fee - stable_amount];
-  let reserves = vec![pool.balance, pool.stable_balance];
-  let supply = ctx.accounts.lpt_mint.supply;
-  pool.total_lpt_fee +=
-    calc_lpt_receive_add_full_side(supply, &amounts, &reserves, pool.fee).unwrap();
   Ok(())
 }
@@ -0,0 +1,133 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct StakeFarm<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(has_one = lpt_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  pub lpt_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Farm's info
+  #[account(
+    mut,
+    has_one = pool,
+    has_one = reward_mint,
+    has_one = vault,
+    has_one = reward_vault
+  )]
+  pub farm: Account<'info, Farm>,
+  pub reward_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Farm's token accounts
+  #[account(mut)]
+  pub vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(mut)]
+  pub reward_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    mut,
+    associated_token::mint = lpt_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
+  )]
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = reward_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = reward_token_program
+  )]
+  pub reward_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Instruction Data
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = Stake::LEN,
+    seeds = [b"stake".as_ref(), &farm.key().to_bytes(), &authority.key().to_bytes()], bump
+  )]
+  pub stake: Account<'info, Stake>,
+  // programs
+  pub system_program: Program<'info, System>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub reward_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<StakeFarm>, amount: u64) -> Result<()> {
+  if !(amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let farm = &mut ctx.accounts.farm;
+  let stake = &mut ctx.accounts.stake;
+  if !(stake.amount > 0) {
+    stake.authority = ctx.accounts.authority.key();
+    stake.farm = farm.key();
+    stake.amount = 0;
+    stake.reward_debt = 0;
+  }
+  farm.update(current_time).ok_or(ErrorCode::MathOverflow)?;
+  // Harvest pending reward
+  let reward_amount = farm
+    .calc_pending_reward(stake.amount, stake.reward_debt)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if reward_amount > 0 {
+    let seeds: &[&[&[u8]]] = &[&[
+      "treasurer".as_ref(),
+      &farm.pool.to_bytes(),
+      &[ctx.bumps.treasurer],
+    ]];
+    token_interface::transfer_checked(
+      CpiContext::new_with_signer(
+        ctx.accounts.reward_token_program.to_account_info(),
+        token_interface::TransferChecked {
+          from: ctx.accounts.reward_vault.to_account_info(),
+          mint: ctx.accounts.reward_mint.to_account_info(),
+          to: ctx.accounts.reward_token_account.to_account_info(),
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      reward_amount,
+      ctx.accounts.reward_mint.decimals,
+    )?;
+  }
+  // Lock lpt token
+  token_interface::transfer_checked(
+    CpiContext::new(
+      ctx.accounts.pool_token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.lpt_token_account.to_account_info(),
+        mint: ctx.accounts.lpt_mint.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+      },
+    ),
+    amount,
+    ctx.accounts.lpt_mint.decimals,
+  )?;
+  // Update farm info
+  stake.amount = stake
+    .amount
+    .checked_add(amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  stake.reward_debt = farm
+    .calc_reward_debt(stake.amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  farm.total_staked = farm
+    .total_staked
+    .checked_add(amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(())
+}
@@ -0,0 +1,100 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct StakeGov<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  #[account(
+    has_one = pool,
+    has_one = gov_mint,
+    seeds = [b"fee_tiers".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub fee_tiers: Account<'info, FeeTiers>,
+  pub gov_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Stake's info
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = FeeStake::LEN,
+    seeds = [
+      b"fee_stake".as_ref(),
+      &pool.key().to_bytes(),
+      &authority.key().to_bytes()
+    ],
+    bump
+  )]
+  pub fee_stake: Account<'info, FeeStake>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    token::mint = gov_mint,
+    token::authority = treasurer,
+    token::token_program = token_program,
+    seeds = [b"fee_stake_escrow".as_ref(), &fee_stake.key().to_bytes()], bump
+  )]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    mut,
+    associated_token::mint = gov_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub gov_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<StakeGov>, amount: u64) -> Result<()> {
+  if !(amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  // The stake counts what the escrow received
+  let amount = transfer_in(
+    &ctx.accounts.token_program,
+    ctx.accounts.gov_token_account.to_account_info(),
+    &mut ctx.accounts.escrow,
+    &ctx.accounts.gov_mint,
+    ctx.accounts.authority.to_account_info(),
+    &[],
+    amount,
+  )?;
+  let pool = &mut ctx.accounts.pool;
+  let fee_stake = &mut ctx.accounts.fee_stake;
+  if !(fee_stake.amount > 0) {
+    pool.open_fee_stakes = pool
+      .open_fee_stakes
+      .checked_add(1)
+      .ok_or(ErrorCode::MathOverflow)?;
+  }
+  fee_stake.pool = pool.key();
+  fee_stake.wallet = ctx.accounts.authority.key();
+  fee_stake.gov_mint = ctx.accounts.gov_mint.key();
+  fee_stake.escrow = ctx.accounts.escrow.key();
+  fee_stake.amount = fee_stake
+    .amount
+    .checked_add(amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Every stake restarts the lock, so the tier can't be bought within one transaction
+  fee_stake.unlock_time = current_time
+    .checked_add(FEE_STAKE_LOCK)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(())
+}
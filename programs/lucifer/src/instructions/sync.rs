@@ -0,0 +1,82 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct SyncPool<'info> {
+  // Pool's info
+  #[account(mut, has_one = mint, has_one = stable_mint, has_one = base_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    associated_token::mint = mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = token_program
+  )]
+  pub treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    associated_token::mint = stable_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    associated_token::mint = base_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
+
+// Overwrite the ledgers with the real treasury amounts, less the referral and lending
+// reserves and the protocol stable backing
+pub fn exec(ctx: Context<SyncPool>) -> Result<()> {
+  let pool = &mut ctx.accounts.pool;
+  if pool.flash_loan_amount > 0 {
+    return err!(ErrorCode::FlashLoanInProgress);
+  }
+  let (amount, stable_amount, base_amount) = pool
+    .calc_treasury_amounts()
+    .ok_or(ErrorCode::MathOverflow)?;
+  let treasury_amount = ctx.accounts.treasury.amount;
+  let stable_treasury_amount = ctx.accounts.stable_treasury.amount;
+  let base_treasury_amount = ctx.accounts.base_treasury.amount;
+  emit!(SyncEvent {
+    pool: pool.key(),
+    discrepancy: calc_discrepancy(treasury_amount, amount).ok_or(ErrorCode::MathOverflow)?,
+    stable_discrepancy: calc_discrepancy(stable_treasury_amount, stable_amount)
+      .ok_or(ErrorCode::MathOverflow)?,
+    base_discrepancy: calc_discrepancy(base_treasury_amount, base_amount)
+      .ok_or(ErrorCode::MathOverflow)?,
+  });
+  pool.balance = treasury_amount
+    .checked_sub(pool.referral_reserve)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.stable_balance = stable_treasury_amount
+    .checked_sub(pool.referral_stable_reserve)
+    .ok_or(ErrorCode::MathOverflow)?;
+  pool.base_balance = base_treasury_amount
+    .checked_sub(pool.lending_reserve)
+    .ok_or(ErrorCode::MathOverflow)?
+    .checked_sub(pool.protocol_backing)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(())
+}
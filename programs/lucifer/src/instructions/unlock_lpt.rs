@@ -0,0 +1,168 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct UnlockLpt<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut, has_one = lpt_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  #[account(mut)]
+  pub lpt_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Farm's info
+  #[account(
+    mut,
+    has_one = pool,
+    has_one = reward_mint,
+    has_one = reward_vault
+  )]
+  pub farm: Account<'info, Farm>,
+  pub reward_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mut)]
+  pub reward_vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Lock's info
+  #[account(
+    mut,
+    close = authority,
+    has_one = authority,
+    has_one = farm,
+    has_one = vault
+  )]
+  pub lp_lock: Account<'info, LpLock>,
+  #[account(mut)]
+  pub vault: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = lpt_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
+  )]
+  pub lpt_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = reward_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = reward_token_program
+  )]
+  pub reward_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub reward_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<UnlockLpt>) -> Result<()> {
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let pool = &mut ctx.accounts.pool;
+  let farm = &mut ctx.accounts.farm;
+  let lp_lock = &ctx.accounts.lp_lock;
+  // Early exit is only possible when the farm charges a penalty
+  let mut penalty = 0;
+  if current_time < lp_lock.unlock_time {
+    if !(farm.early_exit_penalty > 0) {
+      return err!(ErrorCode::StillLocked);
+    }
+    penalty = lp_lock
+      .calc_penalty(farm.early_exit_penalty)
+      .ok_or(ErrorCode::MathOverflow)?;
+  }
+  farm.update(current_time).ok_or(ErrorCode::MathOverflow)?;
+  let reward_amount = farm
+    .calc_pending_reward(lp_lock.weight, lp_lock.reward_debt)
+    .ok_or(ErrorCode::MathOverflow)?;
+  farm.total_staked = farm
+    .total_staked
+    .checked_sub(lp_lock.weight)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Pool signature
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  // Harvest pending reward
+  if reward_amount > 0 {
+    token_interface::transfer_checked(
+      CpiContext::new_with_signer(
+        ctx.accounts.reward_token_program.to_account_info(),
+        token_interface::TransferChecked {
+          from: ctx.accounts.reward_vault.to_account_info(),
+          mint: ctx.accounts.reward_mint.to_account_info(),
+          to: ctx.accounts.reward_token_account.to_account_info(),
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      reward_amount,
+      ctx.accounts.reward_mint.decimals,
+    )?;
+  }
+  // Burning the penalty raises the share of the remaining LPs
+  if penalty > 0 {
+    token_interface::burn(
+      CpiContext::new_with_signer(
+        ctx.accounts.pool_token_program.to_account_info(),
+        token_interface::Burn {
+          from: ctx.accounts.vault.to_account_info(),
+          mint: ctx.accounts.lpt_mint.to_account_info(),
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      penalty,
+    )?;
+    pool.lpt_supply = pool
+      .lpt_supply
+      .checked_sub(penalty)
+      .ok_or(ErrorCode::MathOverflow)?;
+    msg!("Early exit penalty {}", penalty);
+  }
+  pool.open_locks = pool
+    .open_locks
+    .checked_sub(1)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Unlock lpt token
+  let amount = lp_lock
+    .amount
+    .checked_sub(penalty)
+    .ok_or(ErrorCode::MathOverflow)?;
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.pool_token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.vault.to_account_info(),
+        mint: ctx.accounts.lpt_mint.to_account_info(),
+        to: ctx.accounts.lpt_token_account.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    amount,
+    ctx.accounts.lpt_mint.decimals,
+  )?;
+  token_interface::close_account(CpiContext::new_with_signer(
+    ctx.accounts.pool_token_program.to_account_info(),
+    token_interface::CloseAccount {
+      account: ctx.accounts.vault.to_account_info(),
+      destination: ctx.accounts.authority.to_account_info(),
+      authority: ctx.accounts.treasurer.to_account_info(),
+    },
+    seeds,
+  ))?;
+  Ok(())
+}
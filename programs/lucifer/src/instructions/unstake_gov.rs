@@ -0,0 +1,88 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct UnstakeGov<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  pub gov_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Stake's info
+  #[account(
+    mut,
+    has_one = pool,
+    has_one = gov_mint,
+    has_one = escrow,
+    constraint = fee_stake.wallet == authority.key() @ ErrorCode::InvalidFeeProof
+  )]
+  pub fee_stake: Account<'info, FeeStake>,
+  #[account(mut)]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = gov_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = token_program
+  )]
+  pub gov_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn exec(ctx: Context<UnstakeGov>, amount: u64) -> Result<()> {
+  if !(amount > 0) {
+    return err!(ErrorCode::AmountZero);
+  }
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let fee_stake = &mut ctx.accounts.fee_stake;
+  if current_time < fee_stake.unlock_time {
+    return err!(ErrorCode::StillLocked);
+  }
+  fee_stake.amount = fee_stake
+    .amount
+    .checked_sub(amount)
+    .ok_or(ErrorCode::InvalidAmount)?;
+  let pool = &mut ctx.accounts.pool;
+  if !(fee_stake.amount > 0) {
+    pool.open_fee_stakes = pool
+      .open_fee_stakes
+      .checked_sub(1)
+      .ok_or(ErrorCode::MathOverflow)?;
+  }
+  // Pool signature
+  let seeds: &[&[&[u8]]] = &[&[
+    "treasurer".as_ref(),
+    &pool.key().to_bytes(),
+    &[ctx.bumps.treasurer],
+  ]];
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.escrow.to_account_info(),
+        mint: ctx.accounts.gov_mint.to_account_info(),
+        to: ctx.accounts.gov_token_account.to_account_info(),
+        authority: ctx.accounts.treasurer.to_account_info(),
+      },
+      seeds,
+    ),
+    amount,
+    ctx.accounts.gov_mint.decimals,
+  )?;
+  Ok(())
+}
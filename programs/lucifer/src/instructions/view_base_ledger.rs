@@ -0,0 +1,57 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::schema::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct ViewBaseLedger<'info> {
+  // Pool's info
+  #[account(has_one = stable_mint, has_one = base_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    associated_token::mint = base_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
+
+// Report the base buckets: stable backing, lending reserve and outstanding loans
+pub fn exec(ctx: Context<ViewBaseLedger>) -> Result<()> {
+  let pool = &ctx.accounts.pool;
+  let stable_supply = ctx.accounts.stable_mint.supply;
+  // Legacy loans still count as backing until repaid
+  let backing = pool
+    .base_balance
+    .checked_add(pool.legacy_borrowed)
+    .ok_or(ErrorCode::MathOverflow)?;
+  emit!(BaseLedgerEvent {
+    pool: pool.key(),
+    base_treasury_amount: ctx.accounts.base_treasury.amount,
+    stable_supply,
+    backing: pool.base_balance,
+    backing_ratio: pool
+      .calc_backing_ratio(backing, stable_supply)
+      .ok_or(ErrorCode::MathOverflow)?,
+    lending_reserve: pool.lending_reserve,
+    borrowed: pool.borrowed,
+    legacy_borrowed: pool.legacy_borrowed,
+    lending_utilization: pool
+      .calc_lending_utilization()
+      .ok_or(ErrorCode::MathOverflow)?,
+  });
+  Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct ViewSolvency<'info> {
+  // Pool's info
+  #[account(has_one = stable_mint, has_one = base_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    associated_token::mint = base_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // programs
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
+
+// Prove the stable is backed, as return data for simulations and as an event for indexers
+pub fn exec(ctx: Context<ViewSolvency>) -> Result<()> {
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let pool = &ctx.accounts.pool;
+  let stable_supply = ctx.accounts.stable_mint.supply;
+  let base_treasury_amount = ctx.accounts.base_treasury.amount;
+  let collateral =
+    calc_stable_collateral(pool, base_treasury_amount).ok_or(ErrorCode::MathOverflow)?;
+  let collateral_ratio = pool
+    .calc_backing_ratio(collateral, stable_supply)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let proof = SolvencyEvent {
+    pool: pool.key(),
+    timestamp: current_time,
+    stable_supply,
+    base_treasury_amount,
+    lending_reserve: pool.lending_reserve,
+    borrowed: pool.borrowed,
+    legacy_borrowed: pool.legacy_borrowed,
+    vault_debt: pool.vault_debt,
+    collateral,
+    collateral_ratio,
+  };
+  set_return_data(&proof.try_to_vec()?);
+  emit!(proof);
+  Ok(())
+}
@@ -0,0 +1,194 @@
+use crate::errors::ErrorCode;
+use crate::schema::*;
+use crate::utils::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token_interface};
+
+#[derive(Accounts)]
+pub struct WithdrawVault<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+  // Pool's info
+  #[account(mut, has_one = base_mint, has_one = stable_mint)]
+  pub pool: Account<'info, Pool>,
+  #[account(seeds = [b"treasurer", &pool.key().to_bytes()], bump)]
+  /// CHECK: Just a pure account
+  pub treasurer: AccountInfo<'info>,
+  // Pool's Mints
+  pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    mut,
+    seeds = [b"stable_mint".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    mut,
+    seeds = [b"vault_stable_mint".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub vault_stable_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  // Pool's token account
+  #[account(
+    mut,
+    associated_token::mint = stable_mint,
+    associated_token::authority = treasurer,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_treasury: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Vault's info
+  #[account(
+    has_one = pool,
+    has_one = oracle,
+    seeds = [b"vault_config".as_ref(), &pool.key().to_bytes()], bump
+  )]
+  pub vault_config: Account<'info, VaultConfig>,
+  /// CHECK: Parsed as a Pyth-compatible price account
+  pub oracle: AccountInfo<'info>,
+  #[account(mut, has_one = authority, has_one = pool, has_one = escrow)]
+  pub vault: Account<'info, Vault>,
+  #[account(mut)]
+  pub escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Wallet's Token Accounts
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = base_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = base_token_program
+  )]
+  pub base_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = stable_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
+  )]
+  pub stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = vault_stable_mint,
+    associated_token::authority = authority,
+    associated_token::token_program = pool_token_program
+  )]
+  pub vault_stable_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+  // Temporary wrapped SOL account, a fresh keypair that only signs with native
+  #[account(mut)]
+  /// CHECK: Created and closed within the instruction when native
+  pub wsol_account: AccountInfo<'info>,
+
+  // programs
+  pub system_program: Program<'info, System>,
+  pub base_token_program: Interface<'info, token_interface::TokenInterface>,
+  // Program of the stable and LP mints the pool created
+  pub pool_token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+// Repay stable, then take collateral back while staying above the minimum collateral ratio
+pub fn exec(
+  ctx: Context<WithdrawVault>,
+  stable_amount: u64,
+  collateral_amount: u64,
+  native: bool,
+) -> Result<()> {
+  let stable_surplus = calc_surplus(&ctx.accounts.pool, &ctx.accounts.stable_treasury)?;
+  let current_time = current_timestamp().ok_or(ErrorCode::InvalidCurrentTime)?;
+  let vault_config = &ctx.accounts.vault_config;
+  let pool = &mut ctx.accounts.pool;
+  let vault = &mut ctx.accounts.vault;
+  vault
+    .accrue(vault_config.stability_fee, current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  // Repay
+  if stable_amount > 0 {
+    let (principal_amount, fee_amount) =
+      vault.repay(stable_amount).ok_or(ErrorCode::InvalidAmount)?;
+    pool
+      .add_vault_repayment(principal_amount, fee_amount)
+      .ok_or(ErrorCode::MathOverflow)?;
+    repay_vault(
+      &ctx.accounts.pool_token_program,
+      &ctx.accounts.authority,
+      &ctx.accounts.stable_mint,
+      &ctx.accounts.vault_stable_mint,
+      &ctx.accounts.vault_stable_token_account,
+      &ctx.accounts.stable_token_account,
+      &ctx.accounts.stable_treasury,
+      principal_amount,
+      fee_amount,
+    )?;
+  }
+  // Withdraw collateral
+  if collateral_amount > 0 {
+    vault.collateral = vault
+      .collateral
+      .checked_sub(collateral_amount)
+      .ok_or(ErrorCode::InvalidAmount)?;
+    if vault.debt > 0 {
+      let collateral_value = calc_vault_collateral_value(
+        vault_config,
+        pool,
+        &ctx.accounts.oracle,
+        vault.collateral,
+        current_time,
+      )?;
+      let collateral_ratio = vault
+        .calc_collateral_ratio(collateral_value)
+        .ok_or(ErrorCode::MathOverflow)?;
+      if collateral_ratio < vault_config.min_collateral_ratio {
+        return err!(ErrorCode::Undercollateralized);
+      }
+    }
+    let seeds: &[&[&[u8]]] = &[&[
+      "treasurer".as_ref(),
+      &pool.key().to_bytes(),
+      &[ctx.bumps.treasurer],
+    ]];
+    // Pay out as lamports with native
+    let wrapped = native && ctx.accounts.base_token_account.is_native();
+    let base_destination = if wrapped {
+      wrap_sol(
+        &ctx.accounts.system_program,
+        &ctx.accounts.base_token_program,
+        &ctx.accounts.rent,
+        &ctx.accounts.authority,
+        &ctx.accounts.base_mint.to_account_info(),
+        &ctx.accounts.wsol_account,
+        0,
+      )?;
+      ctx.accounts.wsol_account.to_account_info()
+    } else {
+      ctx.accounts.base_token_account.to_account_info()
+    };
+    token_interface::transfer_checked(
+      CpiContext::new_with_signer(
+        ctx.accounts.base_token_program.to_account_info(),
+        token_interface::TransferChecked {
+          from: ctx.accounts.escrow.to_account_info(),
+          mint: ctx.accounts.base_mint.to_account_info(),
+          to: base_destination,
+          authority: ctx.accounts.treasurer.to_account_info(),
+        },
+        seeds,
+      ),
+      collateral_amount,
+      ctx.accounts.base_mint.decimals,
+    )?;
+    if wrapped {
+      unwrap_sol(
+        &ctx.accounts.base_token_program,
+        &ctx.accounts.authority,
+        &ctx.accounts.wsol_account,
+      )?;
+    }
+  }
+  check_ledger(
+    &ctx.accounts.pool,
+    &mut ctx.accounts.stable_treasury,
+    stable_surplus,
+  )?;
+  Ok(())
+}
@@ -14,9 +14,15 @@ pub use instructions::*;
 pub mod errors;
 pub use errors::*;
 
+pub mod events;
+pub use events::*;
+
 pub mod utils;
 pub use utils::*;
 
+#[cfg(test)]
+pub mod test_utils;
+
 #[program]
 pub mod lucifer {
     use super::*;
@@ -27,28 +33,41 @@ pub mod lucifer {
         amount: u64,
         stable_amount: u64,
         base_amount: u64,
+        stable_decimals: u8,
     ) -> Result<()> {
-        initialize_pool::exec(ctx, fee, amount, stable_amount, base_amount)
+        initialize_pool::exec(
+            ctx,
+            fee,
+            amount,
+            stable_amount,
+            base_amount,
+            stable_decimals,
+        )
     }
 
-    pub fn mint_stable(ctx: Context<MintStable>, base_amount: u64) -> Result<()> {
-        mint_stable::exec(ctx, base_amount)
+    pub fn mint_stable(ctx: Context<MintStable>, base_amount: u64, native: bool) -> Result<()> {
+        mint_stable::exec(ctx, base_amount, native)
     }
-    pub fn burn_stable(ctx: Context<BurnStable>, stable_amount: u64) -> Result<()> {
-        burn_stable::exec(ctx, stable_amount)
+    pub fn burn_stable(ctx: Context<BurnStable>, stable_amount: u64, native: bool) -> Result<()> {
+        burn_stable::exec(ctx, stable_amount, native)
     }
 
-    pub fn add_liquidity(
-        ctx: Context<AddLiquidity>,
+    pub fn add_liquidity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>,
         amount: u64,
         stable_amount: u64,
         base_amount: u64,
+        native: bool,
     ) -> Result<()> {
-        add_liquidity::exec(ctx, amount, stable_amount, base_amount)
+        add_liquidity::exec(ctx, amount, stable_amount, base_amount, native)
     }
 
-    pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, lpt_amount: u64) -> Result<()> {
-        remove_liquidity::exec(ctx, lpt_amount)
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        lpt_amount: u64,
+        native: bool,
+    ) -> Result<()> {
+        remove_liquidity::exec(ctx, lpt_amount, native)
     }
 
     pub fn borrow(ctx: Context<Borrow>, lpt_amount: u64) -> Result<()> {
@@ -58,12 +77,21 @@ pub mod lucifer {
     pub fn repay(ctx: Context<Repay>) -> Result<()> {
         repay::exec(ctx)
     }
-    pub fn buy(ctx: Context<Buy>, stable_amount: u64, base_amount: u64) -> Result<()> {
-        buy::exec(ctx, stable_amount, base_amount)
+    pub fn buy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Buy<'info>>,
+        stable_amount: u64,
+        base_amount: u64,
+        native: bool,
+    ) -> Result<()> {
+        buy::exec(ctx, stable_amount, base_amount, native)
     }
 
-    pub fn sell(ctx: Context<Sell>, amount: u64) -> Result<()> {
-        sell::exec(ctx, amount)
+    pub fn sell<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sell<'info>>,
+        amount: u64,
+        native: bool,
+    ) -> Result<()> {
+        sell::exec(ctx, amount, native)
     }
 
     pub fn initialize_jupiter(ctx: Context<InitializeJupiter>) -> Result<()> {
@@ -73,4 +101,297 @@ pub mod lucifer {
     pub fn swap_jupiter(ctx: Context<SwapJupiter>, amount_in: u64, amount_out: u64) -> Result<()> {
         swap_jupiter::exec(ctx, amount_in, amount_out)
     }
+
+    pub fn initialize_farm(
+        ctx: Context<InitializeFarm>,
+        emission_rate: u64,
+        start_time: i64,
+        end_time: i64,
+        reward_amount: u64,
+        early_exit_penalty: u64,
+    ) -> Result<()> {
+        initialize_farm::exec(
+            ctx,
+            emission_rate,
+            start_time,
+            end_time,
+            reward_amount,
+            early_exit_penalty,
+        )
+    }
+
+    pub fn fund_farm(ctx: Context<FundFarm>, reward_amount: u64, end_time: i64) -> Result<()> {
+        fund_farm::exec(ctx, reward_amount, end_time)
+    }
+
+    pub fn close_farm(ctx: Context<CloseFarm>) -> Result<()> {
+        close_farm::exec(ctx)
+    }
+
+    pub fn stake_farm(ctx: Context<StakeFarm>, amount: u64) -> Result<()> {
+        stake_farm::exec(ctx, amount)
+    }
+
+    pub fn unstake_farm(ctx: Context<UnstakeFarm>, amount: u64) -> Result<()> {
+        unstake_farm::exec(ctx, amount)
+    }
+
+    pub fn claim_farm(ctx: Context<ClaimFarm>) -> Result<()> {
+        claim_farm::exec(ctx)
+    }
+
+    pub fn update_flash_loan_fee(
+        ctx: Context<UpdateFlashLoanFee>,
+        flash_loan_fee: u64,
+    ) -> Result<()> {
+        update_flash_loan_fee::exec(ctx, flash_loan_fee)
+    }
+
+    pub fn flash_loan(ctx: Context<FlashLoan>, amount: u64) -> Result<()> {
+        flash_loan::exec(ctx, amount)
+    }
+
+    pub fn flash_repay(ctx: Context<FlashRepay>) -> Result<()> {
+        flash_repay::exec(ctx)
+    }
+
+    pub fn update_curve(ctx: Context<UpdateCurve>, curve: CurveType, amp: u64) -> Result<()> {
+        update_curve::exec(ctx, curve, amp)
+    }
+
+    pub fn ramp_amp(ctx: Context<RampAmp>, amp_target: u64, end_time: i64) -> Result<()> {
+        ramp_amp::exec(ctx, amp_target, end_time)
+    }
+
+    pub fn update_dynamic_fee(
+        ctx: Context<UpdateDynamicFee>,
+        dynamic_fee: bool,
+        min_fee: u64,
+        max_fee: u64,
+        volatility_half_life: i64,
+    ) -> Result<()> {
+        update_dynamic_fee::exec(ctx, dynamic_fee, min_fee, max_fee, volatility_half_life)
+    }
+
+    pub fn update_oracle(
+        ctx: Context<UpdateOracle>,
+        oracle_band: u64,
+        oracle_max_age: i64,
+    ) -> Result<()> {
+        update_oracle::exec(ctx, oracle_band, oracle_max_age)
+    }
+
+    pub fn update_circuit_breaker(
+        ctx: Context<UpdateCircuitBreaker>,
+        max_price_impact: u64,
+        max_window_move: u64,
+        window_duration: i64,
+        halt_duration: i64,
+    ) -> Result<()> {
+        update_circuit_breaker::exec(
+            ctx,
+            max_price_impact,
+            max_window_move,
+            window_duration,
+            halt_duration,
+        )
+    }
+
+    pub fn place_limit_order(
+        ctx: Context<PlaceLimitOrder>,
+        bid_amount: u64,
+        limit_price: u64,
+        expiry_time: i64,
+    ) -> Result<()> {
+        place_limit_order::exec(ctx, bid_amount, limit_price, expiry_time)
+    }
+
+    pub fn fill_limit_order(ctx: Context<FillLimitOrder>) -> Result<()> {
+        fill_limit_order::exec(ctx)
+    }
+
+    pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>) -> Result<()> {
+        cancel_limit_order::exec(ctx)
+    }
+
+    pub fn place_dca_order(
+        ctx: Context<PlaceDcaOrder>,
+        amount: u64,
+        amount_per_interval: u64,
+        interval: i64,
+        max_slippage: u64,
+    ) -> Result<()> {
+        place_dca_order::exec(ctx, amount, amount_per_interval, interval, max_slippage)
+    }
+
+    pub fn execute_dca_order(ctx: Context<ExecuteDcaOrder>) -> Result<()> {
+        execute_dca_order::exec(ctx)
+    }
+
+    pub fn cancel_dca_order(ctx: Context<CancelDcaOrder>) -> Result<()> {
+        cancel_dca_order::exec(ctx)
+    }
+
+    pub fn lock_lpt(ctx: Context<LockLpt>, amount: u64, duration: i64) -> Result<()> {
+        lock_lpt::exec(ctx, amount, duration)
+    }
+
+    pub fn claim_lp_lock(ctx: Context<ClaimLpLock>) -> Result<()> {
+        claim_lp_lock::exec(ctx)
+    }
+
+    pub fn unlock_lpt(ctx: Context<UnlockLpt>) -> Result<()> {
+        unlock_lpt::exec(ctx)
+    }
+
+    pub fn update_referral_fee(ctx: Context<UpdateReferralFee>, referral_fee: u64) -> Result<()> {
+        update_referral_fee::exec(ctx, referral_fee)
+    }
+
+    pub fn initialize_referral(ctx: Context<InitializeReferral>) -> Result<()> {
+        initialize_referral::exec(ctx)
+    }
+
+    pub fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+        claim_referral::exec(ctx)
+    }
+
+    pub fn update_fee_tiers(ctx: Context<UpdateFeeTiers>, tiers: Vec<FeeTier>) -> Result<()> {
+        update_fee_tiers::exec(ctx, tiers)
+    }
+
+    pub fn set_fee_allowance(ctx: Context<SetFeeAllowance>, fee: u64) -> Result<()> {
+        set_fee_allowance::exec(ctx, fee)
+    }
+
+    pub fn revoke_fee_allowance(ctx: Context<RevokeFeeAllowance>) -> Result<()> {
+        revoke_fee_allowance::exec(ctx)
+    }
+
+    pub fn stake_gov(ctx: Context<StakeGov>, amount: u64) -> Result<()> {
+        stake_gov::exec(ctx, amount)
+    }
+
+    pub fn unstake_gov(ctx: Context<UnstakeGov>, amount: u64) -> Result<()> {
+        unstake_gov::exec(ctx, amount)
+    }
+
+    pub fn update_pool_status(ctx: Context<UpdatePoolStatus>, status: PoolStatus) -> Result<()> {
+        update_pool_status::exec(ctx, status)
+    }
+
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        close_pool::exec(ctx)
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        migrate_pool::exec(ctx)
+    }
+
+    pub fn migrate_cheque(ctx: Context<MigrateCheque>) -> Result<()> {
+        migrate_cheque::exec(ctx)
+    }
+
+    pub fn migrate_cert(ctx: Context<MigrateCert>) -> Result<()> {
+        migrate_cert::exec(ctx)
+    }
+
+    pub fn sync(ctx: Context<SyncPool>) -> Result<()> {
+        sync::exec(ctx)
+    }
+
+    pub fn skim(ctx: Context<SkimPool>) -> Result<()> {
+        skim::exec(ctx)
+    }
+
+    pub fn fund_lending_reserve(ctx: Context<FundLendingReserve>, amount: u64) -> Result<()> {
+        fund_lending_reserve::exec(ctx, amount)
+    }
+
+    pub fn withdraw_lending_reserve(
+        ctx: Context<WithdrawLendingReserve>,
+        amount: u64,
+    ) -> Result<()> {
+        withdraw_lending_reserve::exec(ctx, amount)
+    }
+
+    pub fn view_base_ledger(ctx: Context<ViewBaseLedger>) -> Result<()> {
+        view_base_ledger::exec(ctx)
+    }
+
+    pub fn view_solvency(ctx: Context<ViewSolvency>) -> Result<()> {
+        view_solvency::exec(ctx)
+    }
+
+    pub fn initialize_protocol(ctx: Context<InitializeProtocol>, decimals: u8) -> Result<()> {
+        initialize_protocol::exec(ctx, decimals)
+    }
+
+    pub fn set_debt_ceiling(ctx: Context<SetDebtCeiling>, debt_ceiling: u64) -> Result<()> {
+        set_debt_ceiling::exec(ctx, debt_ceiling)
+    }
+
+    pub fn mint_protocol_stable(
+        ctx: Context<MintProtocolStable>,
+        base_amount: u64,
+        native: bool,
+    ) -> Result<()> {
+        mint_protocol_stable::exec(ctx, base_amount, native)
+    }
+
+    pub fn burn_protocol_stable(
+        ctx: Context<BurnProtocolStable>,
+        stable_amount: u64,
+        native: bool,
+    ) -> Result<()> {
+        burn_protocol_stable::exec(ctx, stable_amount, native)
+    }
+
+    pub fn update_vault_config(
+        ctx: Context<UpdateVaultConfig>,
+        oracle_max_age: i64,
+        min_collateral_ratio: u64,
+        liquidation_ratio: u64,
+        liquidation_bonus: u64,
+        stability_fee: u64,
+    ) -> Result<()> {
+        update_vault_config::exec(
+            ctx,
+            oracle_max_age,
+            min_collateral_ratio,
+            liquidation_ratio,
+            liquidation_bonus,
+            stability_fee,
+        )
+    }
+
+    pub fn open_vault(ctx: Context<OpenVault>) -> Result<()> {
+        open_vault::exec(ctx)
+    }
+
+    pub fn deposit_vault(
+        ctx: Context<DepositVault>,
+        collateral_amount: u64,
+        stable_amount: u64,
+        native: bool,
+    ) -> Result<()> {
+        deposit_vault::exec(ctx, collateral_amount, stable_amount, native)
+    }
+
+    pub fn withdraw_vault(
+        ctx: Context<WithdrawVault>,
+        stable_amount: u64,
+        collateral_amount: u64,
+        native: bool,
+    ) -> Result<()> {
+        withdraw_vault::exec(ctx, stable_amount, collateral_amount, native)
+    }
+
+    pub fn liquidate_vault(ctx: Context<LiquidateVault>) -> Result<()> {
+        liquidate_vault::exec(ctx)
+    }
+
+    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+        close_vault::exec(ctx)
+    }
 }
@@ -1,5 +1,6 @@
 // System
 pub const PUBLIC_KEY_SIZE: usize = 32;
+pub const U128_SIZE: usize = 16;
 pub const U64_SIZE: usize = 8;
 pub const F64_SIZE: usize = 8;
 pub const U8_SIZE: usize = 1;
@@ -9,3 +10,36 @@ pub const ACCOUNT_DISCRIMINATOR: usize = 8;
 pub const MINT_LPT_DECIMALS: u8 = 9;
 pub const PRECISION: f64 = 1000000000_f64; // 10^9;
 pub const PRECISION_U64: u64 = 1000000000_u64; // 10^9;
+
+// Farm
+pub const REWARD_PRECISION: u128 = 1000000000000_u128; // 10^12;
+
+// StableSwap
+pub const MIN_AMP: u64 = 1;
+pub const MAX_AMP: u64 = 1000000; // 10^6;
+pub const MAX_AMP_CHANGE: u64 = 10;
+pub const MIN_RAMP_DURATION: i64 = 86400; // 1 day;
+
+// Dynamic fee
+pub const MAX_VOLATILITY_HALF_LIVES: f64 = 64_f64; // fully decayed past this
+
+// Limit order
+pub const LIMIT_ORDER_TIP: u64 = 1000000; // 0.1%;
+
+// LP lock
+pub const MAX_LOCK_DURATION: i64 = 31536000; // 365 days;
+pub const MAX_LOCK_BOOST: u64 = 3000000000; // 3x;
+
+// Fee tiers
+pub const MAX_FEE_TIERS: usize = 8;
+pub const FEE_STAKE_LOCK: i64 = 604800; // 7 days;
+
+// Initial liquidity
+pub const MINIMUM_LIQUIDITY: u64 = 1000; // LPT locked in the treasury forever
+pub const MINIMUM_RESERVE: u64 = 1000000; // per side at pool creation
+
+// Stable decimals
+pub const MAX_DECIMALS_DIFF: u8 = 9;
+
+// CDP vaults
+pub const SECONDS_PER_YEAR: i64 = 31536000; // 365 days;
@@ -6,3 +6,6 @@ pub use f64_trait::*;
 
 pub mod oracle;
 pub use oracle::*;
+
+pub mod stable_swap;
+pub use stable_swap::*;
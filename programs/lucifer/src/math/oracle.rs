@@ -151,3 +151,56 @@ pub fn calc_ask_amount_swap(
   let total_fee = fee.to_f64()?.checked_div(PRECISION)?;
   return Some((ask_amount.checked_mul(1_f64.checked_sub(total_fee)?)?).to_u64()?);
 }
+
+// Return the relative move from one price to another, scaled by PRECISION
+pub fn calc_price_change(from_price: u64, to_price: u64) -> Option<u64> {
+  let change = from_price
+    .max(to_price)
+    .checked_sub(from_price.min(to_price))?;
+  let change_rate = change.to_f64()?.checked_div(from_price.to_f64()?)?;
+  return Some(change_rate.checked_mul(PRECISION)?.to_u64()?);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn swap_from_an_empty_pool_fails() {
+    // Handlers surface these as MathOverflow or InsufficientLiquidity
+    assert_eq!(calc_ask_amount_swap(0, 0, 1000, 0), None);
+    assert_eq!(
+      calc_ask_amount_swap(100, 1000, 1000, 2 * PRECISION_U64),
+      None
+    );
+    assert_eq!(calc_ask_amount_swap(100, 1000, 1000, 0), Some(90));
+  }
+
+  #[test]
+  fn remove_more_than_supply_fails() {
+    let reserves = vec![1000, 2000];
+    assert_eq!(
+      calc_mint_receives_remove_full_side(10, 100, &reserves),
+      Some(vec![100, 200])
+    );
+    assert_eq!(
+      calc_mint_receives_remove_full_side(101, 100, &reserves),
+      None
+    );
+    assert_eq!(calc_mint_receives_remove_full_side(0, 0, &reserves), None);
+  }
+
+  #[test]
+  fn add_to_an_empty_reserve_fails() {
+    assert_eq!(
+      calc_lpt_receive_add_full_side(100, &vec![10, 10], &vec![0, 1000], 0),
+      None
+    );
+  }
+
+  #[test]
+  fn price_change_from_zero_fails() {
+    assert_eq!(calc_price_change(100, 150), Some(PRECISION_U64 / 2));
+    assert_eq!(calc_price_change(0, 150), None);
+  }
+}
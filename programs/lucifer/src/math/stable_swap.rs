@@ -0,0 +1,159 @@
+use crate::constant::*;
+use crate::f64_trait::F64Trait;
+use num_traits::ToPrimitive;
+
+const N_COINS: f64 = 2_f64;
+const MAX_ITERATIONS: usize = 255;
+
+// StableSwap invariant for 2 coins
+/*****************************************************************************************
+// A * n^n * sum(x_i) + D = A * D * n^n + D^(n+1) / (n^n * prod(x_i))                   //
+// Solved by Newton's method:                                                           //
+//          (Ann * S + D_P * n) * D                                                     //
+// D = ---------------------------------    with D_P = D^(n+1) / (n^n * prod(x_i))      //
+//      (Ann - 1) * D + (n + 1) * D_P                                                   //
+ *****************************************************************************************/
+pub fn calc_stable_swap_invariant(amp: u64, reserves: &Vec<u64>) -> Option<f64> {
+  let mut sum = 0_f64;
+  for reserve in reserves {
+    sum = sum.checked_add(reserve.to_f64()?)?;
+  }
+  if sum == 0_f64 {
+    return Some(0_f64);
+  }
+  let ann = amp.to_f64()?.checked_mul(N_COINS.checked_pow(N_COINS)?)?;
+  let mut d = sum;
+  for _ in 0..MAX_ITERATIONS {
+    let mut d_p = d;
+    for reserve in reserves {
+      d_p = d_p
+        .checked_mul(d)?
+        .checked_div(reserve.to_f64()?.checked_mul(N_COINS)?)?;
+    }
+    let d_prev = d;
+    let ann_sum = ann.checked_mul(sum)?;
+    let numerator = (ann_sum.checked_add(d_p.checked_mul(N_COINS)?)?).checked_mul(d)?;
+    let denominator = (ann.checked_sub(1_f64)?.checked_mul(d)?)
+      .checked_add(N_COINS.checked_add(1_f64)?.checked_mul(d_p)?)?;
+    d = numerator.checked_div(denominator)?;
+    if (d - d_prev).abs() <= 1_f64 {
+      return Some(d);
+    }
+  }
+  None
+}
+
+// Return the ask reserve that keeps the invariant D once the bid reserve is new_bid_reserve
+/*****************************************************************************************
+// y^2 + (b - D) * y = c                                                                //
+// b = x + D / Ann                                                                      //
+// c = D^(n+1) / (n^n * x * Ann)                                                        //
+// y = (y^2 + c) / (2 * y + b - D)                                                      //
+ *****************************************************************************************/
+fn calc_stable_swap_reserve(amp: u64, new_bid_reserve: f64, invariant: f64) -> Option<f64> {
+  let ann = amp.to_f64()?.checked_mul(N_COINS.checked_pow(N_COINS)?)?;
+  let c = invariant
+    .checked_mul(invariant)?
+    .checked_div(new_bid_reserve.checked_mul(N_COINS)?)?
+    .checked_mul(invariant)?
+    .checked_div(ann.checked_mul(N_COINS)?)?;
+  let b = new_bid_reserve.checked_add(invariant.checked_div(ann)?)?;
+  let mut y = invariant;
+  for _ in 0..MAX_ITERATIONS {
+    let y_prev = y;
+    let numerator = y.checked_mul(y)?.checked_add(c)?;
+    let denominator = y
+      .checked_mul(2_f64)?
+      .checked_add(b)?
+      .checked_sub(invariant)?;
+    y = numerator.checked_div(denominator)?;
+    if (y - y_prev).abs() <= 1_f64 {
+      return Some(y);
+    }
+  }
+  None
+}
+
+pub fn calc_ask_amount_stable_swap(
+  bid_amount: u64,
+  bid_reserve: u64,
+  ask_reserve: u64,
+  amp: u64,
+  fee: u64,
+) -> Option<u64> {
+  let invariant = calc_stable_swap_invariant(amp, &vec![bid_reserve, ask_reserve])?;
+  let new_bid_reserve = bid_reserve.to_f64()?.checked_add(bid_amount.to_f64()?)?;
+  let new_ask_reserve = calc_stable_swap_reserve(amp, new_bid_reserve, invariant)?;
+  let ask_amount = ask_reserve.to_f64()?.checked_sub(new_ask_reserve)?;
+  if ask_amount < 0_f64 {
+    return Some(0);
+  }
+  // fee
+  let total_fee = fee.to_f64()?.checked_div(PRECISION)?;
+  return Some((ask_amount.checked_mul(1_f64.checked_sub(total_fee)?)?).to_u64()?);
+}
+
+// Return LPT amount will receive after add liquidity on a StableSwap pool
+// The fee is only charged on the part of the deposit that unbalances the pool
+pub fn calc_lpt_receive_add_stable_swap(
+  supply: u64,
+  amounts_in: &Vec<u64>,
+  reserves: &Vec<u64>,
+  amp: u64,
+  total_fee: u64,
+) -> Option<u64> {
+  let d0 = calc_stable_swap_invariant(amp, reserves)?;
+  let mut new_reserves: Vec<u64> = Vec::new();
+  for idx in 0..reserves.len() {
+    new_reserves.push(reserves[idx].checked_add(amounts_in[idx])?);
+  }
+  let d1 = calc_stable_swap_invariant(amp, &new_reserves)?;
+  if d1 <= d0 || d0 <= 0_f64 {
+    return Some(0);
+  }
+  // fee * n / (4 * (n - 1))
+  let fee_rate = total_fee
+    .to_f64()?
+    .checked_div(PRECISION)?
+    .checked_mul(N_COINS)?
+    .checked_div(4_f64.checked_mul(N_COINS.checked_sub(1_f64)?)?)?;
+  let mut reserves_after_fee: Vec<u64> = Vec::new();
+  for idx in 0..reserves.len() {
+    let ideal_reserve = d1.checked_mul(reserves[idx].to_f64()?)?.checked_div(d0)?;
+    let new_reserve = new_reserves[idx].to_f64()?;
+    let difference = (ideal_reserve.checked_sub(new_reserve)?).abs();
+    let reserve_after_fee = new_reserve.checked_sub(fee_rate.checked_mul(difference)?)?;
+    reserves_after_fee.push(reserve_after_fee.to_u64()?);
+  }
+  let d2 = calc_stable_swap_invariant(amp, &reserves_after_fee)?;
+  if d2 <= d0 {
+    return Some(0);
+  }
+  let lpt_out = supply
+    .to_f64()?
+    .checked_mul(d2.checked_sub(d0)?)?
+    .checked_div(d0)?;
+  return Some(lpt_out.to_u64()?);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn swap_against_an_empty_reserve_fails() {
+    assert_eq!(
+      calc_ask_amount_stable_swap(100, 1000, 1000, 100, 0),
+      Some(99)
+    );
+    assert_eq!(calc_ask_amount_stable_swap(100, 0, 1000, 100, 0), None);
+  }
+
+  #[test]
+  fn add_overflowing_reserves_fails() {
+    assert_eq!(
+      calc_lpt_receive_add_stable_swap(100, &vec![1, 0], &vec![u64::MAX, 1000], 100, 0),
+      None
+    );
+  }
+}
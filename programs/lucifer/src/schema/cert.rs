@@ -6,8 +6,16 @@ pub struct Cert {
     pub authority: Pubkey,
     pub pool: Pubkey,
     pub amount: u64,
+    // Layout
+    pub version: u8,
+    pub reserved: [u8; 64],
 }
 
 impl Cert {
-    pub const LEN: usize = ACCOUNT_DISCRIMINATOR + PUBLIC_KEY_SIZE * 2 + U64_SIZE;
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 2
+        + U64_SIZE
+        + U8_SIZE // version
+        + U8_SIZE * 64; // reserved
+    pub const VERSION: u8 = 1;
 }
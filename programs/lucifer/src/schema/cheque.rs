@@ -7,8 +7,16 @@ pub struct Cheque {
     pub pool: Pubkey,
     pub borrow_amount: u64,
     pub base_amount: u64,
+    // Layout
+    pub version: u8,
+    pub reserved: [u8; 64],
 }
 
 impl Cheque {
-    pub const LEN: usize = ACCOUNT_DISCRIMINATOR + PUBLIC_KEY_SIZE * 2 + U64_SIZE * 3;
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 2
+        + U64_SIZE * 3
+        + U8_SIZE // version
+        + U8_SIZE * 64; // reserved
+    pub const VERSION: u8 = 1;
 }
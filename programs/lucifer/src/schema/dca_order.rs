@@ -0,0 +1,44 @@
+use crate::constant::*;
+use crate::f64_trait::F64Trait;
+use anchor_lang::prelude::*;
+use num_traits::ToPrimitive;
+
+#[account]
+pub struct DcaOrder {
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub bid_mint: Pubkey,
+    // PDAs
+    pub escrow: Pubkey,
+    //
+    pub amount_per_interval: u64,
+    pub interval: i64,
+    pub next_execution_time: i64,
+    pub max_slippage: u64,
+    // Spot price at placement, then at the last execution
+    pub reference_price: u64,
+}
+
+impl DcaOrder {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 4
+        + U64_SIZE // amount_per_interval
+        + U64_SIZE // interval
+        + U64_SIZE // next_execution_time
+        + U64_SIZE // max_slippage
+        + U64_SIZE; // reference_price
+
+    // Minimum mint out for a stable slice, from a reference price minus the max slippage
+    pub fn calc_min_amount_out(&self, stable_amount: u64, reference_price: u64) -> Option<u64> {
+        let expected_amount = stable_amount
+            .to_f64()?
+            .checked_mul(PRECISION)?
+            .checked_div(reference_price.to_f64()?)?;
+        let slippage = self.max_slippage.to_f64()?.checked_div(PRECISION)?;
+        return Some(
+            expected_amount
+                .checked_mul(1_f64.checked_sub(slippage)?)?
+                .to_u64()?,
+        );
+    }
+}
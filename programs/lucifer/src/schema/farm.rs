@@ -0,0 +1,165 @@
+use crate::constant::*;
+use crate::f64_trait::F64Trait;
+use anchor_lang::prelude::*;
+use num_traits::ToPrimitive;
+
+#[account]
+pub struct Farm {
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub reward_mint: Pubkey,
+    // PDAs
+    pub vault: Pubkey,
+    pub reward_vault: Pubkey,
+    //
+    pub emission_rate: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub last_reward_time: i64,
+    pub reward_per_share: u128,
+    // Staked LPT plus the boosted weight of locked LPT
+    pub total_staked: u64,
+    pub total_reward: u64,
+    pub early_exit_penalty: u64,
+}
+
+impl Farm {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 5
+        + U64_SIZE // emission_rate
+        + U64_SIZE // start_time
+        + U64_SIZE // end_time
+        + U64_SIZE // last_reward_time
+        + U128_SIZE // reward_per_share
+        + U64_SIZE // total_staked
+        + U64_SIZE // total_reward
+        + U64_SIZE; // early_exit_penalty
+
+    // Total reward the farm will emit between start_time and end_time
+    pub fn calc_total_emission(&self, end_time: i64) -> Option<u64> {
+        let duration = end_time.checked_sub(self.start_time)?.to_u64()?;
+        self.emission_rate.checked_mul(duration)
+    }
+
+    // Accumulate the rewards emitted since last_reward_time into reward_per_share
+    pub fn update(&mut self, current_time: i64) -> Option<()> {
+        let to = current_time.min(self.end_time);
+        if to <= self.last_reward_time {
+            return Some(());
+        }
+        let from = self.last_reward_time.max(self.start_time);
+        if self.total_staked > 0 && to > from {
+            let duration = to.checked_sub(from)?.to_u128()?;
+            let reward = self.emission_rate.to_u128()?.checked_mul(duration)?;
+            let reward_per_share = reward
+                .checked_mul(REWARD_PRECISION)?
+                .checked_div(self.total_staked.to_u128()?)?;
+            self.reward_per_share = self.reward_per_share.checked_add(reward_per_share)?;
+        }
+        self.last_reward_time = to;
+        Some(())
+    }
+
+    pub fn calc_reward_debt(&self, amount: u64) -> Option<u128> {
+        amount
+            .to_u128()?
+            .checked_mul(self.reward_per_share)?
+            .checked_div(REWARD_PRECISION)
+    }
+
+    pub fn calc_pending_reward(&self, amount: u64, reward_debt: u128) -> Option<u64> {
+        self.calc_reward_debt(amount)?
+            .checked_sub(reward_debt)?
+            .to_u64()
+    }
+}
+
+#[account]
+pub struct Stake {
+    pub authority: Pubkey,
+    pub farm: Pubkey,
+    pub amount: u64,
+    pub reward_debt: u128,
+}
+
+impl Stake {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR + PUBLIC_KEY_SIZE * 2 + U64_SIZE + U128_SIZE;
+}
+
+#[account]
+pub struct LpLock {
+    pub authority: Pubkey,
+    pub farm: Pubkey,
+    // PDAs
+    pub vault: Pubkey,
+    //
+    pub amount: u64,
+    pub boost: u64,
+    pub weight: u64,
+    pub unlock_time: i64,
+    pub reward_debt: u128,
+}
+
+impl LpLock {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 3
+        + U64_SIZE // amount
+        + U64_SIZE // boost
+        + U64_SIZE // weight
+        + U64_SIZE // unlock_time
+        + U128_SIZE; // reward_debt
+
+    // Boost grows linearly from 1x to MAX_LOCK_BOOST with the lock duration, scaled by PRECISION
+    pub fn calc_boost(duration: i64) -> Option<u64> {
+        let duration = duration.min(MAX_LOCK_DURATION).to_f64()?;
+        let extra_boost = MAX_LOCK_BOOST.checked_sub(PRECISION_U64)?.to_f64()?;
+        let boost = extra_boost
+            .checked_mul(duration)?
+            .checked_div(MAX_LOCK_DURATION.to_f64()?)?;
+        return Some(PRECISION_U64.checked_add(boost.to_u64()?)?);
+    }
+
+    pub fn calc_weight(amount: u64, boost: u64) -> Option<u64> {
+        amount
+            .to_u128()?
+            .checked_mul(boost.to_u128()?)?
+            .checked_div(PRECISION_U64.to_u128()?)?
+            .to_u64()
+    }
+
+    pub fn calc_penalty(&self, early_exit_penalty: u64) -> Option<u64> {
+        let penalty_rate = early_exit_penalty.to_f64()?.checked_div(PRECISION)?;
+        return Some((self.amount.to_f64()?.checked_mul(penalty_rate)?).to_u64()?);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_reward_below_debt_fails() {
+        let farm = Farm {
+            authority: Pubkey::default(),
+            pool: Pubkey::default(),
+            reward_mint: Pubkey::default(),
+            vault: Pubkey::default(),
+            reward_vault: Pubkey::default(),
+            emission_rate: 0,
+            start_time: 0,
+            end_time: 0,
+            last_reward_time: 0,
+            reward_per_share: REWARD_PRECISION,
+            total_staked: 0,
+            total_reward: 0,
+            early_exit_penalty: 0,
+        };
+        assert_eq!(farm.calc_pending_reward(100, 40), Some(60));
+        assert_eq!(farm.calc_pending_reward(100, 101), None);
+    }
+
+    #[test]
+    fn weight_overflow_fails() {
+        assert_eq!(LpLock::calc_weight(u64::MAX, MAX_LOCK_BOOST), None);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::constant::*;
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct FeeTier {
+    pub min_holding: u64,
+    pub fee: u64,
+}
+
+#[account]
+pub struct FeeTiers {
+    pub pool: Pubkey,
+    pub gov_mint: Pubkey,
+    pub tiers: Vec<FeeTier>,
+}
+
+impl FeeTiers {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE // pool
+        + PUBLIC_KEY_SIZE // gov_mint
+        + VECTOR_SIZE
+        + (U64_SIZE * 2) * MAX_FEE_TIERS; // tiers
+
+    // Lowest fee among the tiers unlocked by a governance token holding
+    pub fn calc_fee(&self, holding: u64) -> Option<u64> {
+        self.tiers
+            .iter()
+            .filter(|tier| holding >= tier.min_holding)
+            .map(|tier| tier.fee)
+            .min()
+    }
+}
+
+#[account]
+pub struct FeeAllowance {
+    pub pool: Pubkey,
+    pub wallet: Pubkey,
+    pub fee: u64,
+}
+
+impl FeeAllowance {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE // pool
+        + PUBLIC_KEY_SIZE // wallet
+        + U64_SIZE; // fee
+}
+
+#[account]
+pub struct FeeStake {
+    pub pool: Pubkey,
+    pub wallet: Pubkey,
+    pub gov_mint: Pubkey,
+    // PDAs
+    pub escrow: Pubkey,
+    //
+    pub amount: u64,
+    pub unlock_time: i64,
+}
+
+impl FeeStake {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 4
+        + U64_SIZE // amount
+        + U64_SIZE; // unlock_time
+}
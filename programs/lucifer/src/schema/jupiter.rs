@@ -5,8 +5,15 @@ use anchor_lang::prelude::*;
 #[account]
 pub struct Jupiter {
     pub base_mint: Pubkey,
+    // Layout, fits in the space of the second pubkey the account always had
+    pub version: u8,
+    pub reserved: [u8; 31],
 }
 
 impl Jupiter {
-    pub const LEN: usize = ACCOUNT_DISCRIMINATOR + PUBLIC_KEY_SIZE * 2;
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE
+        + U8_SIZE // version
+        + U8_SIZE * 31; // reserved
+    pub const VERSION: u8 = 1;
 }
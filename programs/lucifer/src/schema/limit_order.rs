@@ -0,0 +1,60 @@
+use crate::constant::*;
+use crate::f64_trait::F64Trait;
+use anchor_lang::prelude::*;
+use num_traits::ToPrimitive;
+
+#[account]
+pub struct LimitOrder {
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub bid_mint: Pubkey,
+    pub ask_mint: Pubkey,
+    // PDAs
+    pub escrow: Pubkey,
+    //
+    pub bid_amount: u64,
+    pub limit_price: u64,
+    pub expiry_time: i64,
+}
+
+impl LimitOrder {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 5
+        + U64_SIZE // bid_amount
+        + U64_SIZE // limit_price
+        + U64_SIZE; // expiry_time
+
+    // The limit price is the minimum ask received per bid, scaled by PRECISION
+    pub fn calc_min_ask_amount(&self) -> Option<u64> {
+        let limit_price = self.limit_price.to_f64()?.checked_div(PRECISION)?;
+        return Some(
+            self.bid_amount
+                .to_f64()?
+                .checked_mul(limit_price)?
+                .ceil()
+                .to_u64()?,
+        );
+    }
+
+    // Tip paid to the keeper out of the ask amount
+    pub fn calc_keeper_tip(ask_amount: u64) -> Option<u64> {
+        let tip_rate = LIMIT_ORDER_TIP.to_f64()?.checked_div(PRECISION)?;
+        return Some((ask_amount.to_f64()?.checked_mul(tip_rate)?).to_u64()?);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_ask_amount_overflow_fails() {
+        let mut order =
+            LimitOrder::try_deserialize_unchecked(&mut &vec![0_u8; LimitOrder::LEN][..]).unwrap();
+        order.bid_amount = 100;
+        order.limit_price = PRECISION_U64 * 2;
+        assert_eq!(order.calc_min_ask_amount(), Some(200));
+        order.bid_amount = u64::MAX;
+        assert_eq!(order.calc_min_ask_amount(), None);
+    }
+}
@@ -9,3 +9,27 @@ pub use cert::*;
 
 pub mod jupiter;
 pub use jupiter::*;
+
+pub mod farm;
+pub use farm::*;
+
+pub mod price_feed;
+pub use price_feed::*;
+
+pub mod limit_order;
+pub use limit_order::*;
+
+pub mod dca_order;
+pub use dca_order::*;
+
+pub mod referral;
+pub use referral::*;
+
+pub mod fee_tier;
+pub use fee_tier::*;
+
+pub mod protocol;
+pub use protocol::*;
+
+pub mod vault;
+pub use vault::*;
@@ -1,8 +1,34 @@
 use crate::constant::*;
 use crate::f64_trait::F64Trait;
+use crate::oracle::*;
+use crate::stable_swap::*;
 use anchor_lang::prelude::*;
 use num_traits::ToPrimitive;
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CurveType {
+    #[default]
+    Weighted,
+    StableSwap,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PoolStatus {
+    #[default]
+    Active,
+    WithdrawOnly,
+}
+
+// Amount moved from one decimals to another, rounded down
+pub fn calc_scaled_amount(amount: u64, from_decimals: u8, to_decimals: u8) -> Option<u64> {
+    if to_decimals >= from_decimals {
+        let scale = 10_u64.checked_pow((to_decimals - from_decimals).into())?;
+        return amount.checked_mul(scale);
+    }
+    let scale = 10_u64.checked_pow((from_decimals - to_decimals).into())?;
+    amount.checked_div(scale)
+}
+
 #[account]
 pub struct Pool {
     pub authority: Pubkey,
@@ -15,11 +41,72 @@ pub struct Pool {
     //
     pub balance: u64,
     pub stable_balance: u64,
+    // Base backing the stable supply, only moved 1:1 with minted or burned stable
     pub base_balance: u64,
     pub fee: u64,
     pub total_lpt_fee: u64,
     pub lpt_supply: u64,
     pub start_time: i64,
+    // Flash loan
+    pub flash_loan_fee: u64,
+    pub flash_loan_mint: Pubkey,
+    pub flash_loan_amount: u64,
+    // Curve
+    pub curve: CurveType,
+    pub amp_initial: u64,
+    pub amp_target: u64,
+    pub amp_start_time: i64,
+    pub amp_end_time: i64,
+    // Dynamic fee
+    pub dynamic_fee: bool,
+    pub min_fee: u64,
+    pub max_fee: u64,
+    pub volatility_half_life: i64,
+    pub last_price: u64,
+    pub volatility: u64,
+    pub volatility_updated_at: i64,
+    // Oracle guard band
+    pub oracle: Pubkey,
+    pub oracle_band: u64,
+    pub oracle_max_age: i64,
+    // Circuit breaker
+    pub max_price_impact: u64,
+    pub max_window_move: u64,
+    pub window_duration: i64,
+    pub halt_duration: i64,
+    pub window_start_time: i64,
+    pub window_start_price: u64,
+    pub halted_until: i64,
+    // Referral
+    pub referral_fee: u64,
+    // Shutdown
+    pub status: PoolStatus,
+    pub borrowed: u64,
+    // Layout
+    pub version: u8,
+    // Referral shares held in the treasuries until claimed
+    pub referral_reserve: u64,
+    pub referral_stable_reserve: u64,
+    // Lending: base that can be lent on cheques, funded by the authority and fees.
+    // Loans taken before the split were drawn from the backing and repay into it
+    pub lending_reserve: u64,
+    pub legacy_borrowed: u64,
+    // Decimals: stable is scaled from base by the difference. Pools created before
+    // these fields have both at zero and keep minting 1:1 in raw units
+    pub base_decimals: u8,
+    pub stable_decimals: u8,
+    // Base backing the protocol stable minted at this pool
+    pub protocol_backing: u64,
+    // Stable minted against CDP vault collateral and not repaid yet
+    pub vault_debt: u64,
+    // Accounts that hold funds or reference the pool, all must be closed first
+    pub open_orders: u64,
+    pub open_farms: u64,
+    pub open_locks: u64,
+    pub open_vaults: u64,
+    pub open_fee_stakes: u64,
+    // 38 bytes, borsh 0.9 only derives some array lengths
+    pub reserved: [[u8; 19]; 2],
 }
 
 impl Pool {
@@ -31,11 +118,613 @@ impl Pool {
         + U64_SIZE // fee
         + U64_SIZE // total_lpt_fee
         + U64_SIZE // lpt_supply
-        + U64_SIZE; // start_time
+        + U64_SIZE // start_time
+        + U64_SIZE // flash_loan_fee
+        + PUBLIC_KEY_SIZE // flash_loan_mint
+        + U64_SIZE // flash_loan_amount
+        + U8_SIZE // curve
+        + U64_SIZE // amp_initial
+        + U64_SIZE // amp_target
+        + U64_SIZE // amp_start_time
+        + U64_SIZE // amp_end_time
+        + U8_SIZE // dynamic_fee
+        + U64_SIZE // min_fee
+        + U64_SIZE // max_fee
+        + U64_SIZE // volatility_half_life
+        + U64_SIZE // last_price
+        + U64_SIZE // volatility
+        + U64_SIZE // volatility_updated_at
+        + PUBLIC_KEY_SIZE // oracle
+        + U64_SIZE // oracle_band
+        + U64_SIZE // oracle_max_age
+        + U64_SIZE // max_price_impact
+        + U64_SIZE // max_window_move
+        + U64_SIZE // window_duration
+        + U64_SIZE // halt_duration
+        + U64_SIZE // window_start_time
+        + U64_SIZE // window_start_price
+        + U64_SIZE // halted_until
+        + U64_SIZE // referral_fee
+        + U8_SIZE // status
+        + U64_SIZE // borrowed
+        + U8_SIZE // version
+        + U64_SIZE // referral_reserve
+        + U64_SIZE // referral_stable_reserve
+        + U64_SIZE // lending_reserve
+        + U64_SIZE // legacy_borrowed
+        + U8_SIZE // base_decimals
+        + U8_SIZE // stable_decimals
+        + U64_SIZE // protocol_backing
+        + U64_SIZE // vault_debt
+        + U64_SIZE // open_orders
+        + U64_SIZE // open_farms
+        + U64_SIZE // open_locks
+        + U64_SIZE // open_vaults
+        + U64_SIZE // open_fee_stakes
+        + U8_SIZE * 38; // reserved
+
+    // 1: version and padding, 2: lending reserve, 3: decimals, 4: protocol backing,
+    // 5: vault debt, 6: open account counters. Fields only take padding bytes, the
+    // account size never changes
+    pub const VERSION: u8 = 6;
+
+    // Fill the fields added since the stored version, the new bytes are zero
+    pub fn migrate(&mut self) {
+        // Loans taken before versioning are rebuilt from their cheques by migrate_cheque,
+        // the borrowed field didn't cover all of them. Loans of version 1 were drawn from
+        // the stable backing
+        if self.version < 1 {
+            self.borrowed = 0;
+        }
+        if self.version < 2 {
+            self.legacy_borrowed = self.borrowed;
+        }
+        // Zero decimals keep minting 1:1
+        self.version = Pool::VERSION;
+    }
+
+    // A loan of an unversioned cheque, drawn from the stable backing
+    pub fn add_legacy_loan(&mut self, base_amount: u64) -> Option<()> {
+        self.borrowed = self.borrowed.checked_add(base_amount)?;
+        self.legacy_borrowed = self.legacy_borrowed.checked_add(base_amount)?;
+        Some(())
+    }
 
     pub fn calc_fee(&self, amount: u64) -> Option<u64> {
         let amount_f64 = amount.to_f64()?;
         let fee_rate = self.fee.to_f64()?.checked_div(PRECISION)?;
         return Some((amount_f64.checked_mul(fee_rate)?).to_u64()?);
     }
+
+    pub fn calc_flash_loan_fee(&self, amount: u64) -> Option<u64> {
+        let amount_f64 = amount.to_f64()?;
+        let fee_rate = self.flash_loan_fee.to_f64()?.checked_div(PRECISION)?;
+        return Some((amount_f64.checked_mul(fee_rate)?).ceil().to_u64()?);
+    }
+
+    // Amounts the treasury, stable treasury and base treasury should hold per the ledgers
+    pub fn calc_treasury_amounts(&self) -> Option<(u64, u64, u64)> {
+        let amount = self.balance.checked_add(self.referral_reserve)?;
+        let stable_amount = self
+            .stable_balance
+            .checked_add(self.referral_stable_reserve)?;
+        let base_amount = self
+            .base_balance
+            .checked_add(self.lending_reserve)?
+            .checked_add(self.protocol_backing)?;
+        return Some((amount, stable_amount, base_amount));
+    }
+
+    // Stable worth a base amount, rounded down
+    pub fn calc_stable_amount(&self, base_amount: u64) -> Option<u64> {
+        calc_scaled_amount(base_amount, self.base_decimals, self.stable_decimals)
+    }
+
+    // Base worth a stable amount, rounded down
+    pub fn calc_base_amount(&self, stable_amount: u64) -> Option<u64> {
+        calc_scaled_amount(stable_amount, self.stable_decimals, self.base_decimals)
+    }
+
+    // Repaid loans restore the legacy backing first, the rest refills the lending reserve
+    pub fn add_repayment(&mut self, base_amount: u64) -> Option<()> {
+        self.borrowed = self.borrowed.checked_sub(base_amount)?;
+        let legacy_amount = base_amount.min(self.legacy_borrowed);
+        let lending_amount = base_amount.checked_sub(legacy_amount)?;
+        self.legacy_borrowed = self.legacy_borrowed.checked_sub(legacy_amount)?;
+        self.base_balance = self.base_balance.checked_add(legacy_amount)?;
+        self.lending_reserve = self.lending_reserve.checked_add(lending_amount)?;
+        Some(())
+    }
+
+    // Repaid vault principal leaves the vault debt, the stability fee joins the reserves
+    pub fn add_vault_repayment(&mut self, principal_amount: u64, fee_amount: u64) -> Option<()> {
+        self.vault_debt = self.vault_debt.checked_sub(principal_amount)?;
+        self.stable_balance = self.stable_balance.checked_add(fee_amount)?;
+        Some(())
+    }
+
+    // Base backing per stable, in PRECISION units of stable, u64::MAX for an empty supply
+    pub fn calc_backing_ratio(&self, base_backing: u64, stable_supply: u64) -> Option<u64> {
+        if !(stable_supply > 0) {
+            return Some(u64::MAX);
+        }
+        let backing = self.calc_stable_amount(base_backing)?;
+        let ratio = backing
+            .to_u128()?
+            .checked_mul(PRECISION_U64.to_u128()?)?
+            .checked_div(stable_supply.to_u128()?)?;
+        return Some(ratio.min(u64::MAX.to_u128()?).to_u64()?);
+    }
+
+    // Share of the lending bucket currently out on cheques, in PRECISION units
+    pub fn calc_lending_utilization(&self) -> Option<u64> {
+        let borrowed = self.borrowed.checked_sub(self.legacy_borrowed)?;
+        let total = self.lending_reserve.checked_add(borrowed)?;
+        if !(total > 0) {
+            return Some(0);
+        }
+        let utilization = borrowed
+            .to_u128()?
+            .checked_mul(PRECISION_U64.to_u128()?)?
+            .checked_div(total.to_u128()?)?;
+        return Some(utilization.to_u64()?);
+    }
+
+    // The weights are equal, so the weighted invariant moves with the plain product of
+    // the reserves, which compares exactly in integers
+    pub fn calc_weighted_invariant(&self) -> Option<u128> {
+        self.balance
+            .to_u128()?
+            .checked_mul(self.stable_balance.to_u128()?)
+    }
+
+    // Referrer's share of a fee amount
+    pub fn calc_referral_fee(&self, fee_amount: u64) -> Option<u64> {
+        let amount_f64 = fee_amount.to_f64()?;
+        let share = self.referral_fee.to_f64()?.checked_div(PRECISION)?;
+        return Some((amount_f64.checked_mul(share)?).to_u64()?);
+    }
+
+    // Amplification coefficient linearly ramped from amp_initial to amp_target
+    pub fn calc_amp(&self, current_time: i64) -> Option<u64> {
+        if current_time >= self.amp_end_time || self.amp_end_time <= self.amp_start_time {
+            return Some(self.amp_target);
+        }
+        let elapsed = current_time
+            .checked_sub(self.amp_start_time)?
+            .max(0)
+            .to_u64()?;
+        let duration = self
+            .amp_end_time
+            .checked_sub(self.amp_start_time)?
+            .to_u64()?;
+        if self.amp_target > self.amp_initial {
+            let delta = self.amp_target.checked_sub(self.amp_initial)?;
+            let ramped = delta.checked_mul(elapsed)?.checked_div(duration)?;
+            return self.amp_initial.checked_add(ramped);
+        }
+        let delta = self.amp_initial.checked_sub(self.amp_target)?;
+        let ramped = delta.checked_mul(elapsed)?.checked_div(duration)?;
+        self.amp_initial.checked_sub(ramped)
+    }
+
+    pub fn calc_ask_amount(
+        &self,
+        bid_amount: u64,
+        bid_reserve: u64,
+        ask_reserve: u64,
+        fee: u64,
+        current_time: i64,
+    ) -> Option<u64> {
+        match self.curve {
+            CurveType::Weighted => calc_ask_amount_swap(bid_amount, bid_reserve, ask_reserve, fee),
+            CurveType::StableSwap => calc_ask_amount_stable_swap(
+                bid_amount,
+                bid_reserve,
+                ask_reserve,
+                self.calc_amp(current_time)?,
+                fee,
+            ),
+        }
+    }
+
+    pub fn calc_lpt_receive_add(
+        &self,
+        supply: u64,
+        amounts_in: &Vec<u64>,
+        reserves: &Vec<u64>,
+        total_fee: u64,
+        current_time: i64,
+    ) -> Option<u64> {
+        match self.curve {
+            CurveType::Weighted => {
+                calc_lpt_receive_add_full_side(supply, amounts_in, reserves, total_fee)
+            }
+            CurveType::StableSwap => calc_lpt_receive_add_stable_swap(
+                supply,
+                amounts_in,
+                reserves,
+                self.calc_amp(current_time)?,
+                total_fee,
+            ),
+        }
+    }
+
+    // Price of mint in stable, scaled by PRECISION
+    pub fn calc_spot_price(&self) -> Option<u64> {
+        let weight = calc_normalize_weight(0)?;
+        let stable_weight = calc_normalize_weight(1)?;
+        let price = (self.stable_balance.to_f64()?.checked_div(stable_weight)?)
+            .checked_div(self.balance.to_f64()?.checked_div(weight)?)?;
+        return Some(price.checked_mul(PRECISION)?.to_u64()?);
+    }
+
+    // Volatility halves every volatility_half_life seconds
+    pub fn calc_volatility(&self, current_time: i64) -> Option<u64> {
+        if !(self.volatility_half_life > 0) {
+            return Some(0);
+        }
+        let elapsed = current_time
+            .checked_sub(self.volatility_updated_at)?
+            .max(0)
+            .to_f64()?;
+        let half_lives = elapsed.checked_div(self.volatility_half_life.to_f64()?)?;
+        // Deep decays go subnormal and fail the checked math, treat them as fully decayed
+        if half_lives > MAX_VOLATILITY_HALF_LIVES {
+            return Some(0);
+        }
+        let decay = 0.5_f64.checked_pow(half_lives)?;
+        return Some((self.volatility.to_f64()?.checked_mul(decay)?).to_u64()?);
+    }
+
+    // Effective swap fee, between min_fee and max_fee in dynamic mode
+    pub fn calc_swap_fee(&self, current_time: i64) -> Option<u64> {
+        if !self.dynamic_fee {
+            return Some(self.fee);
+        }
+        let fee = self
+            .min_fee
+            .saturating_add(self.calc_volatility(current_time)?);
+        Some(fee.min(self.max_fee))
+    }
+
+    // Accumulate the relative price move since the last trade into the volatility
+    pub fn update_volatility(&mut self, current_time: i64) -> Option<()> {
+        let price = self.calc_spot_price()?;
+        let mut volatility = self.calc_volatility(current_time)?;
+        if self.last_price > 0 {
+            let change_rate = calc_price_change(self.last_price, price)?;
+            volatility = volatility.saturating_add(change_rate);
+        }
+        self.volatility = volatility.min(PRECISION_U64);
+        self.last_price = price;
+        self.volatility_updated_at = current_time;
+        Some(())
+    }
+
+    // Relative distance between the pool price and the oracle price, scaled by PRECISION
+    pub fn calc_oracle_deviation(
+        &self,
+        oracle_price: f64,
+        mint_decimals: u8,
+        stable_decimals: u8,
+    ) -> Option<u64> {
+        let decimals = mint_decimals
+            .to_f64()?
+            .checked_sub(stable_decimals.to_f64()?)?;
+        let price = self
+            .calc_spot_price()?
+            .to_f64()?
+            .checked_div(PRECISION)?
+            .checked_mul(10_f64.checked_pow(decimals)?)?;
+        let deviation = (price.checked_sub(oracle_price)?).abs();
+        return Some(
+            deviation
+                .checked_div(oracle_price)?
+                .checked_mul(PRECISION)?
+                .to_u64()?,
+        );
+    }
+
+    // Oracle price in the units of calc_spot_price
+    pub fn calc_oracle_spot_price(
+        &self,
+        oracle_price: f64,
+        mint_decimals: u8,
+        stable_decimals: u8,
+    ) -> Option<u64> {
+        let decimals = mint_decimals
+            .to_f64()?
+            .checked_sub(stable_decimals.to_f64()?)?;
+        return Some(
+            oracle_price
+                .checked_div(10_f64.checked_pow(decimals)?)?
+                .checked_mul(PRECISION)?
+                .to_u64()?,
+        );
+    }
+
+    // Track the price move within the current window and halt trading once it exceeds
+    // max_window_move. The trade that trips the breaker still goes through, the halt
+    // only applies to the following ones.
+    pub fn update_circuit_breaker(
+        &mut self,
+        pre_price: u64,
+        price: u64,
+        current_time: i64,
+    ) -> Option<bool> {
+        if !(self.max_window_move > 0) {
+            return Some(false);
+        }
+        let window_end_time = self.window_start_time.checked_add(self.window_duration)?;
+        if self.window_start_price == 0 || current_time >= window_end_time {
+            self.window_start_time = current_time;
+            self.window_start_price = pre_price;
+        }
+        let change_rate = calc_price_change(self.window_start_price, price)?;
+        if change_rate > self.max_window_move {
+            self.halted_until = current_time.checked_add(self.halt_duration)?;
+            self.window_start_price = 0;
+            return Some(true);
+        }
+        Some(false)
+    }
+
+    // Apply a swap to the ledgers, return the ask amount and the fee kept by the pool
+    pub fn swap(
+        &mut self,
+        bid_amount: u64,
+        bid_is_stable: bool,
+        fee: u64,
+        lpt_supply: u64,
+        current_time: i64,
+    ) -> Option<(u64, u64)> {
+        let (bid_reserve, ask_reserve) = if bid_is_stable {
+            (self.stable_balance, self.balance)
+        } else {
+            (self.balance, self.stable_balance)
+        };
+        let ask_amount =
+            self.calc_ask_amount(bid_amount, bid_reserve, ask_reserve, fee, current_time)?;
+        let ask_amount_ignore_fee =
+            self.calc_ask_amount(bid_amount, bid_reserve, ask_reserve, 0, current_time)?;
+        let fee_amount = ask_amount_ignore_fee.checked_sub(ask_amount)?;
+        let amounts = if bid_is_stable {
+            self.stable_balance = self.stable_balance.checked_add(bid_amount)?;
+            self.balance = self.balance.checked_sub(ask_amount)?;
+            vec![fee_amount, 0]
+        } else {
+            self.balance = self.balance.checked_add(bid_amount)?;
+            self.stable_balance = self.stable_balance.checked_sub(ask_amount)?;
+            vec![0, fee_amount]
+        };
+        // Update Fee
+        let reserves = vec![self.balance, self.stable_balance];
+        let lpt_fee =
+            self.calc_lpt_receive_add(lpt_supply, &amounts, &reserves, fee, current_time)?;
+        self.total_lpt_fee = self.total_lpt_fee.checked_add(lpt_fee)?;
+        Some((ask_amount, fee_amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::utils::migrate_account;
+
+    fn zeroed_pool() -> Pool {
+        Pool::try_deserialize_unchecked(&mut &vec![0_u8; Pool::LEN][..]).unwrap()
+    }
+
+    // Pool of the first release: 6 pubkeys and 7 u64 up to start_time
+    const BASELINE_LEN: usize = ACCOUNT_DISCRIMINATOR + PUBLIC_KEY_SIZE * 6 + U64_SIZE * 7;
+    // Pool before versioning: 8 pubkeys, 3 enums or bools and 30 u64 up to borrowed
+    const UNVERSIONED_LEN: usize =
+        ACCOUNT_DISCRIMINATOR + PUBLIC_KEY_SIZE * 8 + U8_SIZE * 3 + U64_SIZE * 30;
+
+    fn serialize(pool: &Pool) -> Vec<u8> {
+        let mut data = Vec::new();
+        pool.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    // Realloc an account holding an old layout, as migrate_pool does
+    fn migrate_from(pool: &Pool, len: usize) -> Pool {
+        stub_sysvars();
+        let mut data = serialize(pool);
+        data.truncate(len);
+        let account = reallocable_account_info(Pubkey::new_unique(), crate::ID, data);
+        let payer = leak_info(signer_info(Pubkey::new_unique()));
+        let system_program = leak_info(program_info(System::id()));
+        let mut pool = migrate_account::<Pool>(
+            &account,
+            &Signer::try_from(payer).unwrap(),
+            &Program::try_from(system_program).unwrap(),
+            Pool::LEN,
+        )
+        .unwrap();
+        assert_eq!(account.data_len(), Pool::LEN);
+        assert_eq!(pool.version, 0);
+        pool.migrate();
+        pool
+    }
+
+    #[test]
+    fn layout_size_is_stable() {
+        assert_eq!(serialize(&zeroed_pool()).len(), Pool::LEN);
+        // Version byte and 128 bytes of padding since version 1
+        assert_eq!(Pool::LEN, UNVERSIONED_LEN + U8_SIZE + U8_SIZE * 128);
+    }
+
+    #[test]
+    fn migrates_baseline_layout() {
+        let mut pool = zeroed_pool();
+        pool.balance = 1000;
+        pool.stable_balance = 2000;
+        pool.base_balance = 3000;
+        pool.start_time = 42;
+        pool.fee = 2500000;
+        // Bytes past the old layout are zero-filled whatever the buffer held
+        pool.curve = CurveType::StableSwap;
+        pool.open_orders = 9;
+        let pool = migrate_from(&pool, BASELINE_LEN);
+        assert_eq!(pool.version, Pool::VERSION);
+        assert_eq!(pool.balance, 1000);
+        assert_eq!(pool.stable_balance, 2000);
+        assert_eq!(pool.base_balance, 3000);
+        assert_eq!(pool.start_time, 42);
+        assert_eq!(pool.fee, 2500000);
+        assert_eq!(pool.curve, CurveType::Weighted);
+        assert_eq!(pool.status, PoolStatus::Active);
+        assert_eq!(pool.stable_decimals, 0);
+        assert_eq!(pool.open_orders, 0);
+    }
+
+    #[test]
+    fn migrates_baseline_pool_with_an_outstanding_cheque() {
+        // 1000 stable minted, then 400 base lent on a cheque without tracking it
+        let mut pool = zeroed_pool();
+        pool.base_balance = 600;
+        let mut pool = migrate_from(&pool, BASELINE_LEN);
+        // Unbacked stable isn't taken for a loan
+        assert_eq!(pool.borrowed, 0);
+        assert_eq!(pool.legacy_borrowed, 0);
+        // Until migrate_cheque adds the cheque
+        pool.add_legacy_loan(400).unwrap();
+        assert_eq!(pool.borrowed, 400);
+        assert_eq!(pool.legacy_borrowed, 400);
+        // Repaying the cheque restores the backing instead of overflowing
+        pool.add_repayment(400).unwrap();
+        assert_eq!(pool.borrowed, 0);
+        assert_eq!(pool.legacy_borrowed, 0);
+        assert_eq!(pool.base_balance, 1000);
+        assert_eq!(pool.lending_reserve, 0);
+        assert_eq!(
+            pool.calc_backing_ratio(pool.base_balance, 1000),
+            Some(PRECISION_U64)
+        );
+    }
+
+    #[test]
+    fn migrates_unversioned_layout() {
+        let mut pool = zeroed_pool();
+        pool.borrowed = 100;
+        pool.status = PoolStatus::WithdrawOnly;
+        let pool = migrate_from(&pool, UNVERSIONED_LEN);
+        assert_eq!(pool.version, Pool::VERSION);
+        // Rebuilt from the cheques
+        assert_eq!(pool.borrowed, 0);
+        assert_eq!(pool.legacy_borrowed, 0);
+        assert_eq!(pool.status, PoolStatus::WithdrawOnly);
+    }
+
+    #[test]
+    fn migrates_version_1_loans_as_legacy() {
+        let mut pool = zeroed_pool();
+        pool.version = 1;
+        pool.borrowed = 100;
+        pool.migrate();
+        assert_eq!(pool.version, Pool::VERSION);
+        assert_eq!(pool.borrowed, 100);
+        assert_eq!(pool.legacy_borrowed, 100);
+    }
+
+    #[test]
+    fn migration_keeps_current_fields() {
+        let mut pool = zeroed_pool();
+        pool.version = 2;
+        pool.borrowed = 100;
+        pool.legacy_borrowed = 40;
+        pool.vault_debt = 7;
+        let mut pool = Pool::try_deserialize(&mut &serialize(&pool)[..]).unwrap();
+        pool.migrate();
+        assert_eq!(pool.version, Pool::VERSION);
+        assert_eq!(pool.borrowed, 100);
+        assert_eq!(pool.legacy_borrowed, 40);
+        assert_eq!(pool.vault_debt, 7);
+    }
+
+    #[test]
+    fn swap_from_an_empty_pool_fails() {
+        // Sell and buy surface this as InsufficientLiquidity
+        let mut pool = zeroed_pool();
+        assert_eq!(pool.swap(100, false, 0, 0, 0), None);
+        let mut pool = zeroed_pool();
+        pool.stable_balance = 1000;
+        assert_eq!(pool.swap(100, true, 0, 0, 0), None);
+    }
+
+    #[test]
+    fn treasury_amounts_overflow() {
+        let mut pool = zeroed_pool();
+        pool.base_balance = u64::MAX;
+        pool.lending_reserve = 1;
+        assert_eq!(pool.calc_treasury_amounts(), None);
+    }
+
+    #[test]
+    fn scales_between_6_8_and_9_decimals() {
+        assert_eq!(calc_scaled_amount(1_234_567, 6, 9), Some(1_234_567_000));
+        assert_eq!(calc_scaled_amount(1_234_567, 6, 8), Some(123_456_700));
+        assert_eq!(calc_scaled_amount(123_456_789, 8, 9), Some(1_234_567_890));
+        assert_eq!(calc_scaled_amount(1_234_567_891, 9, 6), Some(1_234_567));
+        assert_eq!(calc_scaled_amount(123_456_789, 8, 6), Some(1_234_567));
+        assert_eq!(calc_scaled_amount(1_234_567_891, 9, 8), Some(123_456_789));
+        assert_eq!(calc_scaled_amount(1_234_567, 9, 9), Some(1_234_567));
+    }
+
+    #[test]
+    fn scaling_rounds_down_both_ways() {
+        // Dust below one unit of the coarser decimals is lost, never created
+        assert_eq!(calc_scaled_amount(999, 9, 6), Some(0));
+        assert_eq!(calc_scaled_amount(1_999, 9, 6), Some(1));
+        let scaled = calc_scaled_amount(calc_scaled_amount(1_999, 9, 6).unwrap(), 6, 9);
+        assert_eq!(scaled, Some(1_000));
+        let scaled = calc_scaled_amount(calc_scaled_amount(1_999, 6, 9).unwrap(), 9, 6);
+        assert_eq!(scaled, Some(1_999));
+    }
+
+    #[test]
+    fn scaling_overflows_at_max_decimals_diff() {
+        let scale = 10_u64.pow(MAX_DECIMALS_DIFF.into());
+        let max_amount = u64::MAX / scale;
+        assert_eq!(
+            calc_scaled_amount(max_amount, 0, MAX_DECIMALS_DIFF),
+            Some(max_amount * scale)
+        );
+        assert_eq!(
+            calc_scaled_amount(max_amount + 1, 0, MAX_DECIMALS_DIFF),
+            None
+        );
+        assert_eq!(
+            calc_scaled_amount(u64::MAX, MAX_DECIMALS_DIFF, 0),
+            Some(max_amount)
+        );
+    }
+
+    #[test]
+    fn volatility_decays_by_half_lives() {
+        let mut pool = zeroed_pool();
+        pool.volatility = 1000;
+        pool.volatility_half_life = 10;
+        assert_eq!(pool.calc_volatility(0), Some(1000));
+        assert_eq!(pool.calc_volatility(10), Some(500));
+        assert_eq!(pool.calc_volatility(20), Some(250));
+    }
+
+    #[test]
+    fn volatility_fully_decays_after_long_idle() {
+        let mut pool = zeroed_pool();
+        pool.volatility = u64::MAX / 2;
+        pool.volatility_half_life = 1;
+        // 2^-1030 is subnormal, the swap fee must not fail on it
+        for elapsed in [65, 1022, 1030, 1075, 1_000_000] {
+            assert_eq!(pool.calc_volatility(elapsed), Some(0));
+        }
+        pool.dynamic_fee = true;
+        pool.min_fee = 2500000;
+        pool.max_fee = 10000000;
+        assert_eq!(pool.calc_swap_fee(1030), Some(2500000));
+    }
 }
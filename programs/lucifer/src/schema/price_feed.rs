@@ -0,0 +1,149 @@
+use crate::f64_trait::F64Trait;
+use num_traits::ToPrimitive;
+
+// Pyth v2 price account layout
+pub const PRICE_FEED_MAGIC: u32 = 0xa1b2c3d4;
+pub const PRICE_FEED_VERSION: u32 = 2;
+pub const PRICE_FEED_ACCOUNT_TYPE: u32 = 3;
+pub const PRICE_FEED_STATUS_TRADING: u32 = 1;
+pub const PRICE_FEED_LEN: usize = 240;
+
+const MAGIC_OFFSET: usize = 0;
+const VERSION_OFFSET: usize = 4;
+const ACCOUNT_TYPE_OFFSET: usize = 8;
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const PRICE_OFFSET: usize = 208;
+const CONF_OFFSET: usize = 216;
+const STATUS_OFFSET: usize = 224;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PriceFeed {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub status: u32,
+    pub timestamp: i64,
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Option<[u8; N]> {
+    data.get(offset..offset.checked_add(N)?)?.try_into().ok()
+}
+
+impl PriceFeed {
+    // Only the layout is checked so a local mock account can stand in for Pyth
+    pub fn parse(data: &[u8]) -> Option<PriceFeed> {
+        if data.len() < PRICE_FEED_LEN {
+            return None;
+        }
+        let magic = u32::from_le_bytes(read_bytes(data, MAGIC_OFFSET)?);
+        let version = u32::from_le_bytes(read_bytes(data, VERSION_OFFSET)?);
+        let account_type = u32::from_le_bytes(read_bytes(data, ACCOUNT_TYPE_OFFSET)?);
+        if magic != PRICE_FEED_MAGIC
+            || version != PRICE_FEED_VERSION
+            || account_type != PRICE_FEED_ACCOUNT_TYPE
+        {
+            return None;
+        }
+        Some(PriceFeed {
+            price: i64::from_le_bytes(read_bytes(data, PRICE_OFFSET)?),
+            conf: u64::from_le_bytes(read_bytes(data, CONF_OFFSET)?),
+            expo: i32::from_le_bytes(read_bytes(data, EXPO_OFFSET)?),
+            status: u32::from_le_bytes(read_bytes(data, STATUS_OFFSET)?),
+            timestamp: i64::from_le_bytes(read_bytes(data, TIMESTAMP_OFFSET)?),
+        })
+    }
+
+    // Mock account data for local tests
+    #[cfg(test)]
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0; PRICE_FEED_LEN];
+        let mut write = |offset: usize, bytes: &[u8]| {
+            data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        };
+        write(MAGIC_OFFSET, &PRICE_FEED_MAGIC.to_le_bytes());
+        write(VERSION_OFFSET, &PRICE_FEED_VERSION.to_le_bytes());
+        write(ACCOUNT_TYPE_OFFSET, &PRICE_FEED_ACCOUNT_TYPE.to_le_bytes());
+        write(PRICE_OFFSET, &self.price.to_le_bytes());
+        write(CONF_OFFSET, &self.conf.to_le_bytes());
+        write(EXPO_OFFSET, &self.expo.to_le_bytes());
+        write(STATUS_OFFSET, &self.status.to_le_bytes());
+        write(TIMESTAMP_OFFSET, &self.timestamp.to_le_bytes());
+        data
+    }
+
+    pub fn is_fresh(&self, current_time: i64, max_age: i64) -> bool {
+        self.status == PRICE_FEED_STATUS_TRADING
+            && self.price > 0
+            && current_time.saturating_sub(self.timestamp) <= max_age
+    }
+
+    // Price as a plain number: price * 10^expo
+    pub fn calc_price(&self) -> Option<f64> {
+        let scale = 10_f64.checked_pow(self.expo.to_f64()?)?;
+        self.price.to_f64()?.checked_mul(scale)
+    }
+
+    // Lower bound of the confidence interval, to value collateral conservatively
+    pub fn calc_min_price(&self) -> Option<f64> {
+        let scale = 10_f64.checked_pow(self.expo.to_f64()?)?;
+        let price = self
+            .price
+            .to_f64()?
+            .checked_sub(self.conf.to_f64()?)?
+            .max(0_f64);
+        price.checked_mul(scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price_feed(price: i64, conf: u64) -> PriceFeed {
+        PriceFeed {
+            price,
+            conf,
+            expo: -2,
+            status: PRICE_FEED_STATUS_TRADING,
+            timestamp: 100,
+        }
+    }
+
+    #[test]
+    fn parses_packed_feed() {
+        let feed = PriceFeed::parse(&price_feed(12345, 45).pack()).unwrap();
+        assert_eq!((feed.price, feed.conf, feed.expo), (12345, 45, -2));
+        assert_eq!(
+            (feed.status, feed.timestamp),
+            (PRICE_FEED_STATUS_TRADING, 100)
+        );
+        assert_eq!(feed.calc_price(), Some(123.45));
+        assert_eq!(feed.calc_min_price(), Some(123.0));
+    }
+
+    #[test]
+    fn rejects_foreign_layouts() {
+        let data = price_feed(12345, 45).pack();
+        assert!(PriceFeed::parse(&data[..PRICE_FEED_LEN - 1]).is_none());
+        for offset in [MAGIC_OFFSET, VERSION_OFFSET, ACCOUNT_TYPE_OFFSET] {
+            let mut data = data.clone();
+            data[offset] ^= 1;
+            assert!(PriceFeed::parse(&data).is_none());
+        }
+    }
+
+    #[test]
+    fn stale_or_halted_feeds_are_not_fresh() {
+        let feed = price_feed(12345, 45);
+        assert!(feed.is_fresh(160, 60));
+        assert!(!feed.is_fresh(161, 60));
+        assert!(!PriceFeed { status: 0, ..feed }.is_fresh(100, 60));
+        assert!(!price_feed(0, 0).is_fresh(100, 60));
+    }
+
+    #[test]
+    fn min_price_floors_at_zero() {
+        assert_eq!(price_feed(100, 200).calc_min_price(), Some(0.0));
+    }
+}
@@ -0,0 +1,45 @@
+use crate::constant::*;
+use crate::schema::pool::*;
+use anchor_lang::prelude::*;
+
+// Singleton owning the stable shared by every pool that opts in
+#[account]
+pub struct Protocol {
+    pub authority: Pubkey,
+    pub stable_mint: Pubkey,
+    pub decimals: u8,
+    pub total_minted: u64,
+}
+
+impl Protocol {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 2
+        + U8_SIZE // decimals
+        + U64_SIZE; // total_minted
+
+    // Protocol stable worth a base amount, rounded down
+    pub fn calc_stable_amount(&self, base_amount: u64, base_decimals: u8) -> Option<u64> {
+        calc_scaled_amount(base_amount, base_decimals, self.decimals)
+    }
+
+    // Base worth a protocol stable amount, rounded down
+    pub fn calc_base_amount(&self, stable_amount: u64, base_decimals: u8) -> Option<u64> {
+        calc_scaled_amount(stable_amount, self.decimals, base_decimals)
+    }
+}
+
+// A pool's share of the protocol stable, capped by its debt ceiling
+#[account]
+pub struct StableMember {
+    pub protocol: Pubkey,
+    pub pool: Pubkey,
+    pub debt_ceiling: u64,
+    pub minted: u64,
+}
+
+impl StableMember {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 2
+        + U64_SIZE // debt_ceiling
+        + U64_SIZE; // minted
+}
@@ -0,0 +1,48 @@
+use crate::constant::*;
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct Referral {
+    pub pool: Pubkey,
+    pub referrer: Pubkey,
+    // Unclaimed fee shares
+    pub reward: u64,
+    pub stable_reward: u64,
+    pub lpt_reward: u64,
+    // Volume brought to the pool
+    pub swap_volume: u64,
+    pub liquidity_volume: u64,
+}
+
+impl Referral {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 2
+        + U64_SIZE // reward
+        + U64_SIZE // stable_reward
+        + U64_SIZE // lpt_reward
+        + U64_SIZE // swap_volume
+        + U64_SIZE; // liquidity_volume
+
+    // Credit a swap, the fee share is paid in the ask token and volume counts the stable leg
+    pub fn add_swap(
+        &mut self,
+        referral_fee: u64,
+        fee_is_stable: bool,
+        stable_volume: u64,
+    ) -> Option<()> {
+        if fee_is_stable {
+            self.stable_reward = self.stable_reward.checked_add(referral_fee)?;
+        } else {
+            self.reward = self.reward.checked_add(referral_fee)?;
+        }
+        self.swap_volume = self.swap_volume.checked_add(stable_volume)?;
+        Some(())
+    }
+
+    // Credit a deposit, the fee share is paid in LPT and volume counts the LPT minted
+    pub fn add_liquidity(&mut self, referral_fee: u64, lpt_amount: u64) -> Option<()> {
+        self.lpt_reward = self.lpt_reward.checked_add(referral_fee)?;
+        self.liquidity_volume = self.liquidity_volume.checked_add(lpt_amount)?;
+        Some(())
+    }
+}
@@ -0,0 +1,212 @@
+use crate::constant::*;
+use crate::f64_trait::F64Trait;
+use anchor_lang::prelude::*;
+use num_traits::ToPrimitive;
+
+// Collateral debt position settings of a pool, ratios and fees are scaled by PRECISION
+#[account]
+pub struct VaultConfig {
+    pub pool: Pubkey,
+    // Price of the base in stable
+    pub oracle: Pubkey,
+    pub oracle_max_age: i64,
+    pub min_collateral_ratio: u64,
+    pub liquidation_ratio: u64,
+    pub liquidation_bonus: u64,
+    // Yearly rate charged on the debt
+    pub stability_fee: u64,
+}
+
+impl VaultConfig {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 2
+        + U64_SIZE // oracle_max_age
+        + U64_SIZE // min_collateral_ratio
+        + U64_SIZE // liquidation_ratio
+        + U64_SIZE // liquidation_bonus
+        + U64_SIZE; // stability_fee
+
+    // Stable worth a collateral amount at the oracle price
+    pub fn calc_collateral_value(
+        &self,
+        collateral: u64,
+        price: f64,
+        base_decimals: u8,
+        stable_decimals: u8,
+    ) -> Option<u64> {
+        let decimals = stable_decimals
+            .to_f64()?
+            .checked_sub(base_decimals.to_f64()?)?;
+        return Some(
+            collateral
+                .to_f64()?
+                .checked_mul(price)?
+                .checked_mul(10_f64.checked_pow(decimals)?)?
+                .to_u64()?,
+        );
+    }
+
+    // Collateral paid to a liquidator for the debt, bonus included
+    pub fn calc_liquidation_collateral(
+        &self,
+        debt: u64,
+        collateral_value: u64,
+        collateral: u64,
+    ) -> Option<u64> {
+        if !(collateral_value > 0) {
+            return Some(collateral);
+        }
+        let bonus = PRECISION.checked_add(self.liquidation_bonus.to_f64()?)?;
+        let seized = debt
+            .to_f64()?
+            .checked_mul(bonus)?
+            .checked_div(PRECISION)?
+            .checked_mul(collateral.to_f64()?)?
+            .checked_div(collateral_value.to_f64()?)?
+            .to_u64()?;
+        return Some(seized.min(collateral));
+    }
+}
+
+#[account]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    // PDAs
+    pub escrow: Pubkey,
+    //
+    pub collateral: u64,
+    // Stable minted and still owed, the debt adds the accrued stability fee
+    pub principal: u64,
+    pub debt: u64,
+    pub last_accrued_at: i64,
+}
+
+impl Vault {
+    pub const LEN: usize = ACCOUNT_DISCRIMINATOR
+        + PUBLIC_KEY_SIZE * 3
+        + U64_SIZE // collateral
+        + U64_SIZE // principal
+        + U64_SIZE // debt
+        + U64_SIZE; // last_accrued_at
+
+    // Simple interest since the last accrual
+    pub fn accrue(&mut self, stability_fee: u64, current_time: i64) -> Option<()> {
+        let elapsed = current_time.checked_sub(self.last_accrued_at)?.max(0);
+        self.last_accrued_at = current_time;
+        if !(self.debt > 0 && elapsed > 0) {
+            return Some(());
+        }
+        let fee = self
+            .debt
+            .to_f64()?
+            .checked_mul(stability_fee.to_f64()?.checked_div(PRECISION)?)?
+            .checked_mul(elapsed.to_f64()?)?
+            .checked_div(SECONDS_PER_YEAR.to_f64()?)?
+            .to_u64()?;
+        self.debt = self.debt.checked_add(fee)?;
+        Some(())
+    }
+
+    // Collateral value per debt, u64::MAX without debt
+    pub fn calc_collateral_ratio(&self, collateral_value: u64) -> Option<u64> {
+        if !(self.debt > 0) {
+            return Some(u64::MAX);
+        }
+        let ratio = collateral_value
+            .to_u128()?
+            .checked_mul(PRECISION_U64.to_u128()?)?
+            .checked_div(self.debt.to_u128()?)?;
+        return Some(ratio.min(u64::MAX.to_u128()?).to_u64()?);
+    }
+
+    // The accrued fee is paid first, returns the principal and fee parts
+    pub fn repay(&mut self, amount: u64) -> Option<(u64, u64)> {
+        let accrued_fee = self.debt.checked_sub(self.principal)?;
+        let fee_paid = amount.min(accrued_fee);
+        let principal_paid = amount.checked_sub(fee_paid)?;
+        self.principal = self.principal.checked_sub(principal_paid)?;
+        self.debt = self.debt.checked_sub(amount)?;
+        Some((principal_paid, fee_paid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault(principal: u64, debt: u64) -> Vault {
+        Vault {
+            authority: Pubkey::default(),
+            pool: Pubkey::default(),
+            escrow: Pubkey::default(),
+            collateral: 0,
+            principal,
+            debt,
+            last_accrued_at: 0,
+        }
+    }
+
+    fn vault_config() -> VaultConfig {
+        VaultConfig {
+            pool: Pubkey::default(),
+            oracle: Pubkey::default(),
+            oracle_max_age: 60,
+            min_collateral_ratio: 1_500_000_000,
+            liquidation_ratio: 1_200_000_000,
+            liquidation_bonus: 100_000_000,
+            stability_fee: 0,
+        }
+    }
+
+    #[test]
+    fn values_collateral_across_decimals() {
+        let vault_config = vault_config();
+        // 2 base of 9 decimals at 25.5 is 51 stable of 6 decimals
+        assert_eq!(
+            vault_config.calc_collateral_value(2_000_000_000, 25.5, 9, 6),
+            Some(51_000_000)
+        );
+        assert_eq!(
+            vault_config.calc_collateral_value(2_000_000, 25.5, 6, 9),
+            Some(51_000_000_000)
+        );
+    }
+
+    #[test]
+    fn liquidation_pays_the_bonus_within_collateral() {
+        let vault_config = vault_config();
+        // 100 debt against 1000 collateral worth 200, 10% bonus
+        assert_eq!(
+            vault_config.calc_liquidation_collateral(100, 200, 1000),
+            Some(550)
+        );
+        assert_eq!(
+            vault_config.calc_liquidation_collateral(300, 200, 1000),
+            Some(1000)
+        );
+        assert_eq!(
+            vault_config.calc_liquidation_collateral(100, 0, 1000),
+            Some(1000)
+        );
+    }
+
+    #[test]
+    fn repay_pays_the_fee_first() {
+        let mut vault = vault(100, 110);
+        assert_eq!(vault.repay(15), Some((5, 10)));
+        assert_eq!((vault.principal, vault.debt), (95, 95));
+    }
+
+    #[test]
+    fn repay_over_debt_fails() {
+        let mut vault = vault(100, 110);
+        assert_eq!(vault.repay(111), None);
+    }
+
+    #[test]
+    fn accrue_overflow_fails() {
+        let mut vault = vault(u64::MAX, u64::MAX);
+        assert_eq!(vault.accrue(PRECISION_U64, SECONDS_PER_YEAR), None);
+    }
+}
@@ -0,0 +1,424 @@
+use crate::schema::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::Bumps;
+use anchor_spl::associated_token::{
+  self, get_associated_token_address, get_associated_token_address_with_program_id,
+};
+use anchor_spl::token::{self, spl_token};
+use anchor_spl::token_2022::{self, spl_token_2022};
+use spl_token_2022::extension::transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig};
+use spl_token_2022::extension::{ExtensionType, StateWithExtensionsMut};
+use std::collections::BTreeSet;
+use std::sync::Once;
+
+pub const CURRENT_TIME: i64 = 1_700_000_000;
+
+// Serve the clock and rent sysvars and the token programs outside the runtime
+struct SysvarStubs;
+
+impl SyscallStubs for SysvarStubs {
+  fn sol_invoke_signed(
+    &self,
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let process = if instruction.program_id == token::ID {
+      spl_token::processor::Processor::process
+    } else if instruction.program_id == token_2022::ID {
+      spl_token_2022::processor::Processor::process
+    } else {
+      return Err(ProgramError::IncorrectProgramId);
+    };
+    let mut accounts = Vec::new();
+    for meta in instruction.accounts.iter() {
+      let mut info = account_infos
+        .iter()
+        .find(|info| *info.key == meta.pubkey)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?
+        .clone();
+      // The program signs for its PDAs with their seeds
+      if meta.is_signer
+        && !info.is_signer
+        && !signers_seeds
+          .iter()
+          .any(|seeds| Pubkey::create_program_address(seeds, &crate::ID) == Ok(meta.pubkey))
+      {
+        return Err(ProgramError::MissingRequiredSignature);
+      }
+      info.is_signer = meta.is_signer;
+      info.is_writable = meta.is_writable;
+      accounts.push(info);
+    }
+    process(&instruction.program_id, &accounts, &instruction.data)
+  }
+  fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+    let clock = Clock {
+      unix_timestamp: CURRENT_TIME,
+      ..Clock::default()
+    };
+    unsafe { *(var_addr as *mut Clock) = clock };
+    0
+  }
+  fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+    unsafe { *(var_addr as *mut Rent) = Rent::default() };
+    0
+  }
+}
+
+static STUBS: Once = Once::new();
+
+pub fn stub_sysvars() {
+  STUBS.call_once(|| {
+    set_syscall_stubs(Box::new(SysvarStubs));
+  });
+}
+
+// Writable, rent exempt account, leaked to live as long as the test
+pub fn account_info(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> AccountInfo<'static> {
+  AccountInfo::new(
+    Box::leak(Box::new(key)),
+    false,
+    true,
+    Box::leak(Box::new(1_000_000_000_u64)),
+    Box::leak(data.into_boxed_slice()),
+    Box::leak(Box::new(owner)),
+    false,
+    0,
+  )
+}
+
+// Instructions borrow their accounts for as long as the account data, so leak the info too
+pub fn leak_info(info: AccountInfo<'static>) -> &'static AccountInfo<'static> {
+  Box::leak(Box::new(info))
+}
+
+// Account laid out as the runtime serializes it, so realloc can grow it in place
+pub fn reallocable_account_info(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> AccountInfo<'static> {
+  let len = data.len();
+  // The original data length sits right before the key
+  let mut key_buffer = (len as u32).to_le_bytes().to_vec();
+  key_buffer.extend_from_slice(&key.to_bytes());
+  let key_buffer = Box::leak(key_buffer.into_boxed_slice());
+  let key = unsafe { &*(key_buffer[4..].as_ptr() as *const Pubkey) };
+  // The data length sits right before the data, with room to grow after it
+  let mut buffer = vec![0; 8 + len + MAX_PERMITTED_DATA_INCREASE];
+  buffer[..8].copy_from_slice(&(len as u64).to_le_bytes());
+  buffer[8..8 + len].copy_from_slice(&data);
+  let buffer = Box::leak(buffer.into_boxed_slice());
+  AccountInfo::new(
+    key,
+    false,
+    true,
+    Box::leak(Box::new(1_000_000_000_u64)),
+    &mut buffer[8..8 + len],
+    Box::leak(Box::new(owner)),
+    false,
+    0,
+  )
+}
+
+pub fn signer_info(key: Pubkey) -> AccountInfo<'static> {
+  let mut info = account_info(key, System::id(), vec![]);
+  info.is_signer = true;
+  info
+}
+
+pub fn program_info(key: Pubkey) -> AccountInfo<'static> {
+  let mut info = account_info(key, Pubkey::default(), vec![]);
+  info.executable = true;
+  info
+}
+
+pub fn rent_info() -> AccountInfo<'static> {
+  let mut info = account_info(sysvar::rent::ID, sysvar::ID, vec![0; Rent::size_of()]);
+  Rent::default().to_account_info(&mut info).unwrap();
+  info
+}
+
+pub fn program_account_info<T: AccountSerialize>(key: Pubkey, account: &T) -> AccountInfo<'static> {
+  let mut data = Vec::new();
+  account.try_serialize(&mut data).unwrap();
+  account_info(key, crate::ID, data)
+}
+
+pub fn mint_info(key: Pubkey, authority: Pubkey, supply: u64) -> AccountInfo<'static> {
+  let mut data = vec![0; spl_token::state::Mint::LEN];
+  spl_token::state::Mint {
+    mint_authority: COption::Some(authority),
+    supply,
+    decimals: 9,
+    is_initialized: true,
+    freeze_authority: COption::None,
+  }
+  .pack_into_slice(&mut data);
+  account_info(key, token::ID, data)
+}
+
+pub fn token_account_info(
+  key: Pubkey,
+  mint: Pubkey,
+  owner: Pubkey,
+  amount: u64,
+) -> AccountInfo<'static> {
+  let mut data = vec![0; spl_token::state::Account::LEN];
+  spl_token::state::Account {
+    mint,
+    owner,
+    amount,
+    state: spl_token::state::AccountState::Initialized,
+    ..spl_token::state::Account::default()
+  }
+  .pack_into_slice(&mut data);
+  account_info(key, token::ID, data)
+}
+
+pub fn associated_token_info(mint: Pubkey, owner: Pubkey, amount: u64) -> AccountInfo<'static> {
+  token_account_info(
+    get_associated_token_address(&owner, &mint),
+    mint,
+    owner,
+    amount,
+  )
+}
+
+// Token-2022 mint withholding the given basis points of every transfer
+pub fn fee_mint_info(
+  key: Pubkey,
+  authority: Pubkey,
+  supply: u64,
+  fee_basis_points: u16,
+) -> AccountInfo<'static> {
+  let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+    ExtensionType::TransferFeeConfig,
+  ])
+  .unwrap();
+  let mut data = vec![0; len];
+  let mut mint =
+    StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data).unwrap();
+  let fee = TransferFee {
+    epoch: 0.into(),
+    maximum_fee: u64::MAX.into(),
+    transfer_fee_basis_points: fee_basis_points.into(),
+  };
+  let config = mint.init_extension::<TransferFeeConfig>(true).unwrap();
+  config.older_transfer_fee = fee;
+  config.newer_transfer_fee = fee;
+  mint.base = spl_token_2022::state::Mint {
+    mint_authority: COption::Some(authority),
+    supply,
+    decimals: 9,
+    is_initialized: true,
+    freeze_authority: COption::None,
+  };
+  mint.pack_base();
+  mint.init_account_type().unwrap();
+  account_info(key, token_2022::ID, data)
+}
+
+// Associated Token-2022 account of a fee mint, with room for the withheld fee
+pub fn fee_associated_token_info(mint: Pubkey, owner: Pubkey, amount: u64) -> AccountInfo<'static> {
+  let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&[
+    ExtensionType::TransferFeeAmount,
+  ])
+  .unwrap();
+  let mut data = vec![0; len];
+  let mut account =
+    StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(&mut data)
+      .unwrap();
+  account.init_extension::<TransferFeeAmount>(true).unwrap();
+  account.base = spl_token_2022::state::Account {
+    mint,
+    owner,
+    amount,
+    state: spl_token_2022::state::AccountState::Initialized,
+    ..spl_token_2022::state::Account::default()
+  };
+  account.pack_base();
+  account.init_account_type().unwrap();
+  let key = get_associated_token_address_with_program_id(&owner, &mint, &token_2022::ID);
+  account_info(key, token_2022::ID, data)
+}
+
+pub fn price_feed_info(key: Pubkey, price_feed: &PriceFeed) -> AccountInfo<'static> {
+  account_info(key, Pubkey::new_unique(), price_feed.pack())
+}
+
+pub fn find_pda(seeds: &[&[u8]]) -> Pubkey {
+  Pubkey::find_program_address(seeds, &crate::ID).0
+}
+
+// A pool with its mints and treasurer, every field not set here is zero
+pub struct PoolFixture {
+  pub key: Pubkey,
+  pub pool: Pool,
+}
+
+impl Default for PoolFixture {
+  fn default() -> PoolFixture {
+    let key = Pubkey::new_unique();
+    let mut pool = Pool::try_deserialize_unchecked(&mut &vec![0_u8; Pool::LEN][..]).unwrap();
+    pool.authority = Pubkey::new_unique();
+    pool.mint = Pubkey::new_unique();
+    pool.base_mint = Pubkey::new_unique();
+    pool.stable_mint = find_pda(&[b"stable_mint", &key.to_bytes()]);
+    pool.lpt_mint = find_pda(&[b"lpt_mint", &key.to_bytes()]);
+    pool.treasurer = find_pda(&[b"treasurer", &key.to_bytes()]);
+    pool.version = Pool::VERSION;
+    PoolFixture { key, pool }
+  }
+}
+
+impl PoolFixture {
+  pub fn new() -> PoolFixture {
+    PoolFixture::default()
+  }
+
+  pub fn pool_info(&self) -> AccountInfo<'static> {
+    program_account_info(self.key, &self.pool)
+  }
+
+  pub fn treasurer_info(&self) -> AccountInfo<'static> {
+    account_info(self.pool.treasurer, System::id(), vec![])
+  }
+
+  pub fn mint_info(&self, mint: Pubkey) -> AccountInfo<'static> {
+    mint_info(mint, self.pool.treasurer, 0)
+  }
+
+  pub fn treasury_info(&self, mint: Pubkey) -> AccountInfo<'static> {
+    associated_token_info(mint, self.pool.treasurer, 0)
+  }
+
+  // The wallet's cheque, naming the given pool, at the size borrow allocates
+  pub fn cheque_info(&self, authority: Pubkey, cheque_pool: Pubkey) -> AccountInfo<'static> {
+    let cheque = Cheque {
+      authority,
+      pool: cheque_pool,
+      borrow_amount: 100,
+      base_amount: 100,
+      version: Cheque::VERSION,
+      reserved: [0; 64],
+    };
+    let key = find_pda(&[b"cheque", &self.key.to_bytes(), &authority.to_bytes()]);
+    let mut data = Vec::new();
+    cheque.try_serialize(&mut data).unwrap();
+    data.resize(Cheque::LEN, 0);
+    account_info(key, crate::ID, data)
+  }
+
+  // The wallet, the pool and its treasurer, how the account lists start
+  pub fn pool_accounts(&self, authority: Pubkey) -> Vec<AccountInfo<'static>> {
+    vec![
+      signer_info(authority),
+      self.pool_info(),
+      self.treasurer_info(),
+    ]
+  }
+
+  // Borrow and repay take the same accounts
+  pub fn cheque_accounts(
+    &self,
+    authority: Pubkey,
+    base_mint: Pubkey,
+    cheque: AccountInfo<'static>,
+  ) -> Vec<AccountInfo<'static>> {
+    let lpt_mint = self.pool.lpt_mint;
+    [
+      self.pool_accounts(authority),
+      vec![
+        self.mint_info(base_mint),
+        self.mint_info(lpt_mint),
+        self.treasury_info(base_mint),
+        self.treasury_info(lpt_mint),
+        associated_token_info(base_mint, authority, 0),
+        associated_token_info(lpt_mint, authority, 0),
+        cheque,
+      ],
+      program_infos(2),
+    ]
+    .concat()
+  }
+}
+
+// The programs and the rent sysvar, how the account lists end. Instructions take a
+// token program per group of mints, all of them the token program here
+pub fn program_infos(token_programs: usize) -> Vec<AccountInfo<'static>> {
+  [
+    vec![program_info(System::id())],
+    (0..token_programs)
+      .map(|_| program_info(token::ID))
+      .collect(),
+    vec![program_info(associated_token::ID), rent_info()],
+  ]
+  .concat()
+}
+
+// Run the account constraints of an instruction. The accounts must outlive the
+// instruction, so the list is leaked like the account data
+pub fn try_accounts<'info, T>(accounts: &[AccountInfo<'info>]) -> Result<T>
+where
+  T: Bumps + Accounts<'info, T::Bumps>,
+  T::Bumps: Default,
+{
+  stub_sysvars();
+  let mut accounts: &'info [AccountInfo<'info>] = Vec::leak(accounts.to_vec());
+  T::try_accounts(
+    &crate::ID,
+    &mut accounts,
+    &[],
+    &mut T::Bumps::default(),
+    &mut BTreeSet::new(),
+  )
+}
+
+// Run an instruction as the program entrypoint does: constraints, handler, then exit
+pub fn exec_instruction<'info, T>(
+  accounts: &[AccountInfo<'info>],
+  exec: impl FnOnce(Context<'_, '_, 'info, 'info, T>) -> Result<()>,
+) -> Result<()>
+where
+  T: Bumps + Accounts<'info, T::Bumps> + AccountsExit<'info>,
+  T::Bumps: Default,
+{
+  stub_sysvars();
+  let mut remaining_accounts: &'info [AccountInfo<'info>] = Vec::leak(accounts.to_vec());
+  let mut bumps = T::Bumps::default();
+  let mut accounts = T::try_accounts(
+    &crate::ID,
+    &mut remaining_accounts,
+    &[],
+    &mut bumps,
+    &mut BTreeSet::new(),
+  )?;
+  exec(Context::new(
+    &crate::ID,
+    &mut accounts,
+    remaining_accounts,
+    bumps,
+  ))?;
+  accounts.exit(&crate::ID)
+}
+
+// The error code the account constraints of an instruction fail with, None when they pass
+pub fn constraint_error<'info, T>(accounts: &[AccountInfo<'info>]) -> Option<u32>
+where
+  T: Bumps + Accounts<'info, T::Bumps>,
+  T::Bumps: Default,
+{
+  let error = try_accounts::<T>(accounts).err()?;
+  Some(error_code::<()>(Err(error)).expect("constraints fail with anchor errors"))
+}
+
+pub fn error_code<T>(result: Result<T>) -> Option<u32> {
+  match result {
+    Err(Error::AnchorError(error)) => Some(error.error_code_number),
+    _ => None,
+  }
+}
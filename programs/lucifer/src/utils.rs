@@ -1,6 +1,641 @@
+use crate::constant::*;
+use crate::errors::ErrorCode;
+use crate::oracle::*;
+use crate::schema::*;
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+  transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::{token, token_interface};
+use num_traits::ToPrimitive;
 
 pub fn current_timestamp() -> Option<i64> {
   let clock = Clock::get().ok()?;
   Some(clock.unix_timestamp)
 }
+
+pub fn find_account<'a, 'info>(
+  accounts: &'a [AccountInfo<'info>],
+  key: &Pubkey,
+) -> Option<&'a AccountInfo<'info>> {
+  accounts.iter().find(|account| account.key == key)
+}
+
+// Reject the pool state if its price left the oracle band or the oracle is stale
+pub fn check_oracle_band(
+  pool: &Pool,
+  remaining_accounts: &[AccountInfo],
+  mint_decimals: u8,
+  stable_decimals: u8,
+  current_time: i64,
+) -> Result<()> {
+  let oracle_price = match load_oracle_price(pool, remaining_accounts, current_time)? {
+    Some(oracle_price) => oracle_price,
+    None => return Ok(()),
+  };
+  let deviation = pool
+    .calc_oracle_deviation(oracle_price, mint_decimals, stable_decimals)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if deviation > pool.oracle_band {
+    return err!(ErrorCode::OracleBandExceeded);
+  }
+  Ok(())
+}
+
+// Fresh price of the pool's oracle, none if the pool has no oracle
+pub fn load_oracle_price(
+  pool: &Pool,
+  remaining_accounts: &[AccountInfo],
+  current_time: i64,
+) -> Result<Option<f64>> {
+  if pool.oracle == Pubkey::default() {
+    return Ok(None);
+  }
+  let oracle = find_account(remaining_accounts, &pool.oracle).ok_or(ErrorCode::MissingOracle)?;
+  let price_feed = PriceFeed::parse(&oracle.try_borrow_data()?).ok_or(ErrorCode::InvalidOracle)?;
+  if !price_feed.is_fresh(current_time, pool.oracle_max_age) {
+    return err!(ErrorCode::StaleOracle);
+  }
+  let oracle_price = price_feed.calc_price().ok_or(ErrorCode::MathOverflow)?;
+  Ok(Some(oracle_price))
+}
+
+// Stable worth a CDP vault collateral amount at the conservative oracle price
+pub fn calc_vault_collateral_value(
+  vault_config: &VaultConfig,
+  pool: &Pool,
+  oracle: &AccountInfo,
+  collateral: u64,
+  current_time: i64,
+) -> Result<u64> {
+  let price_feed = PriceFeed::parse(&oracle.try_borrow_data()?).ok_or(ErrorCode::InvalidOracle)?;
+  if !price_feed.is_fresh(current_time, vault_config.oracle_max_age) {
+    return err!(ErrorCode::StaleOracle);
+  }
+  let price = price_feed.calc_min_price().ok_or(ErrorCode::MathOverflow)?;
+  let collateral_value = vault_config
+    .calc_collateral_value(collateral, price, pool.base_decimals, pool.stable_decimals)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(collateral_value)
+}
+
+// Repay CDP vault debt: the principal is burned as vault stable and the stability fee
+// goes to the LPs in stable
+pub fn repay_vault<'info>(
+  pool_token_program: &Interface<'info, token_interface::TokenInterface>,
+  authority: &Signer<'info>,
+  stable_mint: &InterfaceAccount<'info, token_interface::Mint>,
+  vault_stable_mint: &InterfaceAccount<'info, token_interface::Mint>,
+  vault_stable_token_account: &InterfaceAccount<'info, token_interface::TokenAccount>,
+  stable_token_account: &InterfaceAccount<'info, token_interface::TokenAccount>,
+  stable_treasury: &InterfaceAccount<'info, token_interface::TokenAccount>,
+  principal_amount: u64,
+  fee_amount: u64,
+) -> Result<()> {
+  if principal_amount > 0 {
+    token_interface::burn(
+      CpiContext::new(
+        pool_token_program.to_account_info(),
+        token_interface::Burn {
+          from: vault_stable_token_account.to_account_info(),
+          mint: vault_stable_mint.to_account_info(),
+          authority: authority.to_account_info(),
+        },
+      ),
+      principal_amount,
+    )?;
+  }
+  if fee_amount > 0 {
+    token_interface::transfer_checked(
+      CpiContext::new(
+        pool_token_program.to_account_info(),
+        token_interface::TransferChecked {
+          from: stable_token_account.to_account_info(),
+          mint: stable_mint.to_account_info(),
+          to: stable_treasury.to_account_info(),
+          authority: authority.to_account_info(),
+        },
+      ),
+      fee_amount,
+      stable_mint.decimals,
+    )?;
+  }
+  Ok(())
+}
+
+// Move tokens into an account of the program and return what arrived. Mints with a
+// transfer fee withhold part of the amount, so ledgers are credited from the balance delta
+pub fn transfer_in<'info>(
+  token_program: &Interface<'info, token_interface::TokenInterface>,
+  from: AccountInfo<'info>,
+  to: &mut InterfaceAccount<'info, token_interface::TokenAccount>,
+  mint: &InterfaceAccount<'info, token_interface::Mint>,
+  authority: AccountInfo<'info>,
+  signer_seeds: &[&[&[u8]]],
+  amount: u64,
+) -> Result<u64> {
+  to.reload()?;
+  let before = to.amount;
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from,
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority,
+      },
+      signer_seeds,
+    ),
+    amount,
+    mint.decimals,
+  )?;
+  to.reload()?;
+  let received = to
+    .amount
+    .checked_sub(before)
+    .ok_or(ErrorCode::MathOverflow)?;
+  Ok(received)
+}
+
+// Amount to send so that the given amount arrives, adding the transfer fee of Token-2022
+// mints that withhold one. Fixed debts are paid in full this way
+pub fn calc_transfer_amount(
+  mint: &InterfaceAccount<token_interface::Mint>,
+  amount: u64,
+) -> Result<u64> {
+  let mint_info = mint.to_account_info();
+  let data = mint_info.try_borrow_data()?;
+  let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+  let config = match state.get_extension::<TransferFeeConfig>() {
+    Ok(config) => config,
+    Err(_) => return Ok(amount),
+  };
+  let epoch = Clock::get()?.epoch;
+  let fee = config
+    .calculate_inverse_epoch_fee(epoch, amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  let amount = amount.checked_add(fee).ok_or(ErrorCode::MathOverflow)?;
+  Ok(amount)
+}
+
+// Withdraw-only pools only let LPs, stable holders and borrowers exit
+pub fn check_active(pool: &Pool) -> Result<()> {
+  if pool.status != PoolStatus::Active {
+    return err!(ErrorCode::PoolNotActive);
+  }
+  Ok(())
+}
+
+pub fn check_trading_halt(pool: &Pool, current_time: i64) -> Result<()> {
+  if current_time < pool.halted_until {
+    return err!(ErrorCode::TradingHalted);
+  }
+  Ok(())
+}
+
+// Per-trade price impact limit, then feed the per-window circuit breaker
+pub fn check_price_impact(pool: &mut Pool, pre_price: u64, current_time: i64) -> Result<()> {
+  let price = pool.calc_spot_price().ok_or(ErrorCode::MathOverflow)?;
+  if pool.max_price_impact > 0 {
+    let price_impact = calc_price_change(pre_price, price).ok_or(ErrorCode::MathOverflow)?;
+    if price_impact > pool.max_price_impact {
+      return err!(ErrorCode::PriceImpactExceeded);
+    }
+  }
+  let halted = pool
+    .update_circuit_breaker(pre_price, price, current_time)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if halted {
+    msg!("Trading halted until {}", pool.halted_until);
+  }
+  Ok(())
+}
+
+// Open a temporary wrapped SOL account holding `amount` lamports on top of its rent.
+// It is a fresh keypair signed by the wallet, so the wallet's own WSOL account is
+// never touched, and it must be closed by unwrap_sol within the same instruction.
+pub fn wrap_sol<'info>(
+  system_program: &Program<'info, System>,
+  token_program: &Interface<'info, token_interface::TokenInterface>,
+  rent: &Sysvar<'info, Rent>,
+  authority: &Signer<'info>,
+  native_mint: &AccountInfo<'info>,
+  wsol_account: &AccountInfo<'info>,
+  amount: u64,
+) -> Result<()> {
+  let lamports = rent
+    .minimum_balance(token::TokenAccount::LEN)
+    .checked_add(amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  system_program::create_account(
+    CpiContext::new(
+      system_program.to_account_info(),
+      system_program::CreateAccount {
+        from: authority.to_account_info(),
+        to: wsol_account.clone(),
+      },
+    ),
+    lamports,
+    token::TokenAccount::LEN as u64,
+    &token_program.key(),
+  )?;
+  // The token balance of a native account starts at its lamports above rent
+  token_interface::initialize_account(CpiContext::new(
+    token_program.to_account_info(),
+    token_interface::InitializeAccount {
+      account: wsol_account.clone(),
+      mint: native_mint.clone(),
+      authority: authority.to_account_info(),
+      rent: rent.to_account_info(),
+    },
+  ))
+}
+
+// Close the temporary wrapped SOL account so its balance and rent go back as lamports
+pub fn unwrap_sol<'info>(
+  token_program: &Interface<'info, token_interface::TokenInterface>,
+  authority: &Signer<'info>,
+  wsol_account: &AccountInfo<'info>,
+) -> Result<()> {
+  token_interface::close_account(CpiContext::new(
+    token_program.to_account_info(),
+    token_interface::CloseAccount {
+      account: wsol_account.clone(),
+      destination: authority.to_account_info(),
+      authority: authority.to_account_info(),
+    },
+  ))
+}
+
+// First account of the given type among the remaining accounts, only writable ones
+// when the handler persists changes to it
+pub fn find_program_account<'info, T>(
+  program_id: &Pubkey,
+  remaining_accounts: &'info [AccountInfo<'info>],
+  writable: bool,
+) -> Result<Option<Account<'info, T>>>
+where
+  T: AccountSerialize + AccountDeserialize + Owner + Clone + Discriminator,
+{
+  for account in remaining_accounts {
+    if account.owner != program_id || (writable && !account.is_writable) {
+      continue;
+    }
+    let data = account.try_borrow_data()?;
+    if data.len() < ACCOUNT_DISCRIMINATOR || data[..ACCOUNT_DISCRIMINATOR] != T::discriminator() {
+      continue;
+    }
+    drop(data);
+    return Ok(Some(Account::<T>::try_from(account)?));
+  }
+  Ok(None)
+}
+
+// Optional referral ledger, passed in the remaining accounts
+pub fn find_referral<'info>(
+  program_id: &Pubkey,
+  pool: &Pubkey,
+  authority: &Pubkey,
+  remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<Option<Account<'info, Referral>>> {
+  let referral = find_program_account::<Referral>(program_id, remaining_accounts, true)?;
+  if let Some(referral) = &referral {
+    if referral.pool != *pool {
+      return err!(ErrorCode::InvalidReferral);
+    }
+    if referral.referrer == *authority {
+      return err!(ErrorCode::SelfReferral);
+    }
+  }
+  Ok(referral)
+}
+
+// Discounted fee for the trader, proven by a fee allowance or by a governance token
+// stake next to the pool's fee tiers
+pub fn calc_discounted_fee<'info>(
+  program_id: &Pubkey,
+  pool: &Pubkey,
+  authority: &Pubkey,
+  remaining_accounts: &'info [AccountInfo<'info>],
+  fee: u64,
+) -> Result<u64> {
+  let mut discounted_fee = fee;
+  if let Some(allowance) =
+    find_program_account::<FeeAllowance>(program_id, remaining_accounts, false)?
+  {
+    if allowance.pool != *pool || allowance.wallet != *authority {
+      return err!(ErrorCode::InvalidFeeProof);
+    }
+    discounted_fee = discounted_fee.min(allowance.fee);
+  }
+  if let Some(fee_tiers) = find_program_account::<FeeTiers>(program_id, remaining_accounts, false)?
+  {
+    if fee_tiers.pool != *pool {
+      return err!(ErrorCode::InvalidFeeProof);
+    }
+    // Only staked governance tokens count, a wallet balance could be flash-borrowed
+    let mut holding = 0;
+    if let Some(fee_stake) =
+      find_program_account::<FeeStake>(program_id, remaining_accounts, false)?
+    {
+      if fee_stake.pool != *pool
+        || fee_stake.wallet != *authority
+        || fee_stake.gov_mint != fee_tiers.gov_mint
+      {
+        return err!(ErrorCode::InvalidFeeProof);
+      }
+      holding = fee_stake.amount;
+    }
+    if let Some(tier_fee) = fee_tiers.calc_fee(holding) {
+      discounted_fee = discounted_fee.min(tier_fee);
+    }
+  }
+  Ok(discounted_fee)
+}
+
+// Set the referrer's share of a swap fee aside from the pool ledgers
+pub fn credit_referral_swap<'info>(
+  program_id: &Pubkey,
+  pool: &mut Account<Pool>,
+  authority: &Pubkey,
+  remaining_accounts: &'info [AccountInfo<'info>],
+  fee_amount: u64,
+  fee_is_stable: bool,
+  stable_volume: u64,
+) -> Result<()> {
+  let mut referral = match find_referral(program_id, &pool.key(), authority, remaining_accounts)? {
+    Some(referral) => referral,
+    None => return Ok(()),
+  };
+  let referral_fee = pool
+    .calc_referral_fee(fee_amount)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if fee_is_stable {
+    pool.stable_balance = pool
+      .stable_balance
+      .checked_sub(referral_fee)
+      .ok_or(ErrorCode::MathOverflow)?;
+    pool.referral_stable_reserve = pool
+      .referral_stable_reserve
+      .checked_add(referral_fee)
+      .ok_or(ErrorCode::MathOverflow)?;
+  } else {
+    pool.balance = pool
+      .balance
+      .checked_sub(referral_fee)
+      .ok_or(ErrorCode::MathOverflow)?;
+    pool.referral_reserve = pool
+      .referral_reserve
+      .checked_add(referral_fee)
+      .ok_or(ErrorCode::MathOverflow)?;
+  }
+  referral
+    .add_swap(referral_fee, fee_is_stable, stable_volume)
+    .ok_or(ErrorCode::MathOverflow)?;
+  referral.exit(program_id)?;
+  Ok(())
+}
+
+// Grow an account of the program to the current layout of T, the payer tops up the rent.
+// Old layouts are prefixes of the current one, the new fields are zero-filled.
+pub fn migrate_account<'info, T>(
+  account: &AccountInfo<'info>,
+  payer: &Signer<'info>,
+  system_program: &Program<'info, System>,
+  len: usize,
+) -> Result<T>
+where
+  T: AccountDeserialize + Discriminator,
+{
+  {
+    let data = account.try_borrow_data()?;
+    if data.len() < ACCOUNT_DISCRIMINATOR || data[..ACCOUNT_DISCRIMINATOR] != T::discriminator() {
+      return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+  }
+  if account.data_len() < len {
+    let rent = Rent::get()?.minimum_balance(len);
+    let lamports = rent.saturating_sub(account.lamports());
+    if lamports > 0 {
+      system_program::transfer(
+        CpiContext::new(
+          system_program.to_account_info(),
+          system_program::Transfer {
+            from: payer.to_account_info(),
+            to: account.clone(),
+          },
+        ),
+        lamports,
+      )?;
+    }
+    account.realloc(len, true)?;
+  }
+  let data = account.try_borrow_data()?;
+  T::try_deserialize(&mut &data[..])
+}
+
+// Signed difference between a treasury amount and its ledger
+pub fn calc_discrepancy(treasury_amount: u64, ledger_amount: u64) -> Option<i64> {
+  treasury_amount
+    .to_i128()?
+    .checked_sub(ledger_amount.to_i128()?)?
+    .to_i64()
+}
+
+// What a treasury holds above its ledger, an open flash loan excepted. The surplus is
+// donated tokens, sync takes them into the ledgers and skim sends them out
+pub fn calc_surplus(pool: &Pool, treasury: &token_interface::TokenAccount) -> Result<u64> {
+  let (amount, stable_amount, base_amount) = pool
+    .calc_treasury_amounts()
+    .ok_or(ErrorCode::MathOverflow)?;
+  let mut ledger_amount = if treasury.mint == pool.mint {
+    amount
+  } else if treasury.mint == pool.stable_mint {
+    stable_amount
+  } else if treasury.mint == pool.base_mint {
+    base_amount
+  } else {
+    return Ok(0);
+  };
+  if treasury.mint == pool.flash_loan_mint {
+    ledger_amount = ledger_amount.saturating_sub(pool.flash_loan_amount);
+  }
+  let surplus = treasury
+    .amount
+    .checked_sub(ledger_amount)
+    .ok_or(ErrorCode::LedgerMismatch)?;
+  Ok(surplus)
+}
+
+// Surplus of the treasury, stable treasury and base treasury
+pub fn calc_surpluses(
+  pool: &Pool,
+  treasury: &token_interface::TokenAccount,
+  stable_treasury: &token_interface::TokenAccount,
+  base_treasury: &token_interface::TokenAccount,
+) -> Result<(u64, u64, u64)> {
+  Ok((
+    calc_surplus(pool, treasury)?,
+    calc_surplus(pool, stable_treasury)?,
+    calc_surplus(pool, base_treasury)?,
+  ))
+}
+
+// Handlers move a treasury and its ledger together: the treasury ends with the surplus
+// it had when the handler started, no more and no less
+pub fn check_ledger<'info>(
+  pool: &Pool,
+  treasury: &mut InterfaceAccount<'info, token_interface::TokenAccount>,
+  surplus: u64,
+) -> Result<()> {
+  treasury.reload()?;
+  if calc_surplus(pool, treasury)? != surplus {
+    return err!(ErrorCode::LedgerMismatch);
+  }
+  Ok(())
+}
+
+// Base backing the stable: the treasury outside the lending reserve and the protocol
+// stable backing, plus loans taken before the lending reserve existed and an open base
+// flash loan
+pub fn calc_stable_collateral(pool: &Pool, base_treasury_amount: u64) -> Option<u64> {
+  let mut collateral = base_treasury_amount
+    .saturating_sub(pool.lending_reserve)
+    .saturating_sub(pool.protocol_backing)
+    .checked_add(pool.legacy_borrowed)?;
+  if pool.flash_loan_mint == pool.base_mint {
+    collateral = collateral.checked_add(pool.flash_loan_amount)?;
+  }
+  Some(collateral)
+}
+
+// Every stable in circulation is backed by base, vaults mint their own stable
+pub fn check_stable_backing<'info>(
+  pool: &Pool,
+  stable_mint: &mut InterfaceAccount<'info, token_interface::Mint>,
+  base_treasury: &mut InterfaceAccount<'info, token_interface::TokenAccount>,
+) -> Result<()> {
+  stable_mint.reload()?;
+  base_treasury.reload()?;
+  let collateral =
+    calc_stable_collateral(pool, base_treasury.amount).ok_or(ErrorCode::MathOverflow)?;
+  let backing = pool
+    .calc_stable_amount(collateral)
+    .ok_or(ErrorCode::MathOverflow)?;
+  if stable_mint.supply > backing {
+    return err!(ErrorCode::UnbackedStable);
+  }
+  Ok(())
+}
+
+// Solvency checks run at the end of the handlers that move pool tokens
+pub fn check_invariants<'info>(
+  pool: &Pool,
+  treasury: &mut InterfaceAccount<'info, token_interface::TokenAccount>,
+  stable_treasury: &mut InterfaceAccount<'info, token_interface::TokenAccount>,
+  base_treasury: &mut InterfaceAccount<'info, token_interface::TokenAccount>,
+  stable_mint: &mut InterfaceAccount<'info, token_interface::Mint>,
+  surpluses: (u64, u64, u64),
+) -> Result<()> {
+  let (surplus, stable_surplus, base_surplus) = surpluses;
+  check_ledger(pool, treasury, surplus)?;
+  check_ledger(pool, stable_treasury, stable_surplus)?;
+  check_ledger(pool, base_treasury, base_surplus)?;
+  check_stable_backing(pool, stable_mint, base_treasury)
+}
+
+// Swaps may only grow the weighted invariant, fees included
+pub fn check_swap_invariant(pool: &Pool, pre_invariant: u128) -> Result<()> {
+  if pool.curve != CurveType::Weighted {
+    return Ok(());
+  }
+  let invariant = pool
+    .calc_weighted_invariant()
+    .ok_or(ErrorCode::MathOverflow)?;
+  if invariant < pre_invariant {
+    return err!(ErrorCode::InvariantDecreased);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils::*;
+
+  fn vault_config(oracle: Pubkey) -> VaultConfig {
+    VaultConfig {
+      pool: Pubkey::default(),
+      oracle,
+      oracle_max_age: 60,
+      min_collateral_ratio: 1_500_000_000,
+      liquidation_ratio: 1_200_000_000,
+      liquidation_bonus: 100_000_000,
+      stability_fee: 0,
+    }
+  }
+
+  fn price_feed(price: i64, conf: u64, timestamp: i64) -> PriceFeed {
+    PriceFeed {
+      price,
+      conf,
+      expo: -2,
+      status: PRICE_FEED_STATUS_TRADING,
+      timestamp,
+    }
+  }
+
+  fn pool() -> Pool {
+    let mut pool = PoolFixture::new().pool;
+    pool.base_decimals = 9;
+    pool.stable_decimals = 6;
+    pool
+  }
+
+  #[test]
+  fn values_vault_collateral_at_the_min_oracle_price() {
+    let oracle = Pubkey::new_unique();
+    let oracle_info = price_feed_info(oracle, &price_feed(2550, 50, CURRENT_TIME));
+    // 2 base at 25.5 with a 0.5 confidence is worth 50 stable
+    let value = calc_vault_collateral_value(
+      &vault_config(oracle),
+      &pool(),
+      &oracle_info,
+      2_000_000_000,
+      CURRENT_TIME,
+    );
+    assert_eq!(value.unwrap(), 50_000_000);
+  }
+
+  #[test]
+  fn rejects_stale_oracles() {
+    let oracle = Pubkey::new_unique();
+    let oracle_info = price_feed_info(oracle, &price_feed(2550, 50, CURRENT_TIME - 61));
+    let value = calc_vault_collateral_value(
+      &vault_config(oracle),
+      &pool(),
+      &oracle_info,
+      2_000_000_000,
+      CURRENT_TIME,
+    );
+    assert_eq!(error_code(value), Some(ErrorCode::StaleOracle.into()));
+  }
+
+  #[test]
+  fn rejects_foreign_oracle_accounts() {
+    let oracle = Pubkey::new_unique();
+    let oracle_info = account_info(oracle, Pubkey::new_unique(), vec![0; PRICE_FEED_LEN]);
+    let value = calc_vault_collateral_value(
+      &vault_config(oracle),
+      &pool(),
+      &oracle_info,
+      2_000_000_000,
+      CURRENT_TIME,
+    );
+    assert_eq!(error_code(value), Some(ErrorCode::InvalidOracle.into()));
+  }
+}